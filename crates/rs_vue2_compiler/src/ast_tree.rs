@@ -1,23 +1,26 @@
-use crate::directives_model::gen_assignment_code;
+use crate::codegen::to_js_string;
+use crate::directives_model::{gen_assignment_code, is_assignable};
 use crate::filter_parser::parse_filters;
 use crate::helpers::{is_some_and_ref, to_camel, to_hyphen_case};
 use crate::text_parser::parse_text;
 use crate::uni_codes::{UC_KEY, UC_V_ELSE, UC_V_ELSE_IF, UC_V_FOR, UC_V_IF, UC_V_ONCE, UC_V_PRE};
 use crate::util::{
-    get_attribute_value, modifier_regex_replace_all_matches, prepend_modifier_marker,
+    get_attribute_value, modifier_matches, modifier_regex_replace_all_matches,
+    prepend_modifier_marker,
 };
 use crate::warn_logger::WarnLogger;
-use crate::web::attrs::must_use_prop;
+use crate::web::attrs::{must_use_prop, svg_attr_name};
+use crate::web::element::is_reserved_tag;
 use crate::{
     CompilerOptions, ARG_RE, BIND_RE, DIR_RE, DIR_RE_VBIND_SHORT_HAND, DYNAMIC_ARG_RE,
-    FOR_ALIAS_RE, FOR_ITERATOR_RE, MODIFIER_RE, ON_RE, PROP_BIND_RE, SLOT_RE, STRIP_PARENS_RE,
+    FOR_ALIAS_RE, FOR_ITERATOR_RE, ON_RE, PROP_BIND_RE, SLOT_RE, STRIP_PARENS_RE,
 };
 use regex::Regex;
 use rs_html_parser_tokenizer_tokens::QuoteType;
 use rs_html_parser_tokens::Token;
 use rs_html_parser_tokens::TokenKind::{OpenTag, ProcessingInstruction};
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::rc::{Rc, Weak};
 use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
@@ -26,37 +29,85 @@ use unicase_collections::unicase_btree_set::UniCaseBTreeSet;
 pub const EMPTY_SLOT_SCOPE_TOKEN: &'static str = "_empty_";
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AttrItem {
     pub name: String,
     pub value: Option<String>,
     pub dynamic: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub quote_type: QuoteType,
+    // the value is the attribute text as written, not a javascript expression
+    pub literal: bool,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Handler {
     pub value: String,
     pub dynamic: bool,
-    pub modifiers: UniCaseBTreeSet,
+    // in the order they were written, the guards they generate run in that order
+    pub modifiers: Vec<String>,
+    // the handler expression as written in the attribute value, see ASTElement::attr_ranges
+    pub range: Option<SourceRange>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Directive {
     pub name: String,
     pub raw_name: String,
     pub value: Option<String>,
     pub arg: Option<String>,
     pub is_dynamic_arg: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::unicase_set")
+    )]
     pub modifiers: UniCaseBTreeSet,
 }
 
+// v-bind="object" without an argument, binds every key of the object
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VBindObject {
+    pub value: String,
+    pub prop: bool,
+    pub sync: bool,
+}
+
+// byte offsets into the template, line and column of the start are 1-based
+// and the column counts characters
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SourceRange {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct IfCondition {
     pub exp: Option<String>,
     pub block_id: usize,
 }
 
+// how the runtime has to normalize the children array of an element
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum NormalizationType {
+    // children are plain vnodes
+    #[default]
+    None = 0,
+    // a child may be a component which can return an array of nodes
+    Simple = 1,
+    // a child is a v-for, <template> or <slot> which can expand to nested arrays
+    Full = 2,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ASTElementKind {
     Root = 0,
     Element = 1,
@@ -72,12 +123,22 @@ pub struct AttrEntry {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ASTElement {
     // rs_html_parser_tokens Token
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "data", serialize_with = "crate::serialize::token_data")
+    )]
     pub token: Token,
+    // from the start tag to the end of the close tag, elements without one end with their start tag
+    // and text nodes cover their text. Only set with CompilerOptions::output_source_range
+    pub range: Option<SourceRange>,
 
     pub expression: Option<String>,
     pub tokens: Option<Vec<String>>,
+    // filters used by the expressions of this node
+    pub filters: Vec<String>,
 
     pub static_class: Option<String>,
     pub class_binding: Option<String>,
@@ -86,23 +147,41 @@ pub struct ASTElement {
     pub forbidden: bool,
     pub pre: bool,
     pub plain: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub ignored: UniCaseBTreeSet,
     pub processed: bool,
     pub ref_val: Option<String>,
     pub ref_in_for: bool,
-    pub ns: Option<&'static str>,
+    pub ns: Option<String>,
 
+    #[cfg_attr(feature = "serde", serde(rename = "is"))]
     pub component: Option<String>,
     pub inline_template: bool,
-
+    // the element renders the component being compiled, see CompilerOptions::component_name
+    pub is_self_reference: bool,
+
+    // raw attribute names in the order they were written in the template
+    pub attr_names: Vec<String>,
+    // attribute values without the quotes, only set with CompilerOptions::output_source_range
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::unicase_map")
+    )]
+    pub attr_ranges: UniCaseBTreeMap<SourceRange>,
     pub attrs: Vec<AttrItem>,
     pub dynamic_attrs: Vec<AttrItem>,
     pub props: Vec<AttrItem>,
 
     pub directives: Option<Vec<Directive>>,
+    pub v_bind_object: Option<VBindObject>,
+    // v-on="object" without an argument, the listeners are merged into the data with `_g`
+    pub v_on_object: Option<String>,
+    // bindings carrying the compat `.number` modifier, see CompilerOptions::compat_number_modifier
+    pub number_bindings: Vec<String>,
 
-    pub events: Option<UniCaseBTreeMap<Vec<Handler>>>,
-    pub native_events: Option<UniCaseBTreeMap<Vec<Handler>>>,
+    // event names are case sensitive, components can emit both `myEvent` and `myevent`
+    pub events: Option<BTreeMap<String, Vec<Handler>>>,
+    pub native_events: Option<BTreeMap<String, Vec<Handler>>>,
 
     pub key: Option<String>,
 
@@ -117,6 +196,8 @@ pub struct ASTElement {
     pub if_processed: bool,
     pub else_if_val: Option<String>,
     pub is_else: bool,
+    // serialized by the node, which nests the branch blocks inline
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub if_conditions: Option<Vec<IfCondition>>,
 
     pub once: bool,
@@ -124,11 +205,25 @@ pub struct ASTElement {
     pub slot_name: Option<String>,
     pub slot_target: Option<String>,
     pub slot_target_dynamic: bool,
+    // the slot was declared with v-slot, not with the deprecated slot and slot-scope attributes
+    pub v_slot: bool,
     pub slot_scope: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub scoped_slots: Option<UniCaseBTreeMap<Rc<RefCell<ASTNode>>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "crate::serialize::is_default")
+    )]
+    pub normalization_type: NormalizationType,
+    // set by the optimizer
+    pub is_static: bool,
+    pub static_root: bool,
+    pub static_in_for: bool,
     pub has_bindings: bool,
     pub kind: ASTElementKind,
     pub is_comment: bool,
+    // text inside a <pre> element, its whitespace is kept as written
+    pub in_pre: bool,
     pub static_style: Option<String>,
     pub style_binding: Option<String>,
 }
@@ -137,7 +232,9 @@ pub fn create_ast_element(token: Token, kind: ASTElementKind) -> ASTElement {
     ASTElement {
         kind,
         token,
+        range: None,
         expression: None,
+        filters: vec![],
         forbidden: false,
         pre: false,
         plain: false,
@@ -161,19 +258,31 @@ pub fn create_ast_element(token: Token, kind: ASTElementKind) -> ASTElement {
         ns: None,
         component: None,
         inline_template: false,
+        is_self_reference: false,
+        attr_names: vec![],
+        attr_ranges: UniCaseBTreeMap::new(),
         attrs: vec![],
         scoped_slots: None,
+        normalization_type: NormalizationType::None,
+        is_static: false,
+        static_root: false,
+        static_in_for: false,
         slot_scope: None,
         dynamic_attrs: vec![],
         slot_target_dynamic: false,
+        v_slot: false,
         static_class: None,
         has_bindings: false,
         props: vec![],
         directives: None,
+        v_bind_object: None,
+        v_on_object: None,
+        number_bindings: vec![],
         events: None,
         native_events: None,
         tokens: None,
         is_comment: false,
+        in_pre: false,
         static_style: None,
         class_binding: None,
         style_binding: None,
@@ -205,9 +314,24 @@ impl fmt::Debug for ASTNode {
     }
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseStats {
+    // deepest element nesting reached, the root element is at depth 1
+    pub max_depth: usize,
+    // names of all the filters referenced by the template expressions
+    pub filters: BTreeSet<String>,
+    // subtrees marked as static roots by the optimizer
+    pub static_roots: usize,
+}
+
 #[derive(Debug)]
 pub struct ASTTree {
     pub wrapper: Rc<RefCell<ASTNode>>,
+    pub stats: ParseStats,
+    // warnings raised while parsing, only collected when CompilerOptions::warn is not set
+    pub warnings: Vec<String>,
+    // the first top-level element, v-else(-if) branches and extra roots are not it
+    pub root_id: Option<usize>,
     counter: Cell<usize>,
     nodes: HashMap<usize, Rc<RefCell<ASTNode>>>,
 }
@@ -235,6 +359,9 @@ impl ASTTree {
         let mut tree = ASTTree {
             counter: Cell::new(0),
             wrapper: Rc::clone(&node),
+            stats: ParseStats::default(),
+            warnings: vec![],
+            root_id: None,
             nodes: Default::default(),
         };
 
@@ -244,7 +371,7 @@ impl ASTTree {
     }
 
     pub fn create(
-        &self,
+        &mut self,
         element: ASTElement,
         parent_id: usize,
         is_dev: bool,
@@ -263,6 +390,7 @@ impl ASTTree {
             is_dev,
             warn,
         }));
+        self.nodes.insert(new_id, Rc::clone(&new_node));
 
         new_node
     }
@@ -274,6 +402,125 @@ impl ASTTree {
     pub fn set(&mut self, id: usize, node: Rc<RefCell<ASTNode>>) {
         self.nodes.insert(id, node);
     }
+
+    /// Detaches the node from its parent and drops it and its descendants from the tree,
+    /// including scoped slots and v-else(-if) branches. A removed branch is unlinked from the
+    /// conditions of its v-if element. The wrapper node cannot be removed.
+    /// A parent that is borrowed while its children are removed has to detach them itself.
+    pub fn remove(&mut self, id: usize) -> Option<Rc<RefCell<ASTNode>>> {
+        if id == 0 {
+            return None;
+        }
+        let node_ptr = self.nodes.remove(&id)?;
+        if self.root_id == Some(id) {
+            self.root_id = None;
+        }
+
+        let parent_id = node_ptr.borrow().parent_id;
+        if let Some(Ok(mut parent)) = self.get(parent_id).map(|parent| parent.try_borrow_mut()) {
+            parent
+                .children
+                .retain(|child| !Rc::ptr_eq(child, &node_ptr));
+            if let Some(scoped_slots) = &mut parent.el.scoped_slots {
+                scoped_slots.retain(|_, slot| !Rc::ptr_eq(slot, &node_ptr));
+            }
+            // branches are not children, only the v-if element before them refers to them
+            let siblings = parent.children.iter().chain(
+                parent
+                    .el
+                    .scoped_slots
+                    .iter()
+                    .flat_map(|slots| slots.values()),
+            );
+            for sibling in siblings {
+                if let Some(if_conditions) = &mut sibling.borrow_mut().el.if_conditions {
+                    if_conditions.retain(|condition| condition.block_id != id);
+                }
+            }
+        }
+
+        let mut descendants: Vec<Rc<RefCell<ASTNode>>> = vec![Rc::clone(&node_ptr)];
+        while let Some(descendant) = descendants.pop() {
+            let descendant = descendant.borrow();
+            let branches = descendant
+                .el
+                .if_conditions
+                .iter()
+                .flatten()
+                .filter(|condition| condition.block_id != descendant.id)
+                .filter_map(|condition| self.get(condition.block_id).cloned());
+            let mut pending: Vec<Rc<RefCell<ASTNode>>> = branches.collect();
+            pending.extend(
+                descendant
+                    .el
+                    .scoped_slots
+                    .iter()
+                    .flat_map(|slots| slots.values())
+                    .cloned(),
+            );
+            pending.extend(descendant.children.iter().cloned());
+
+            for node in &pending {
+                self.nodes.remove(&node.borrow().id);
+            }
+            descendants.extend(pending);
+        }
+
+        Some(node_ptr)
+    }
+
+    /// Depth-first traversal of the template nodes in document order, starting with the root
+    /// element. The wrapper node is not included. Scoped slots of an element come before its
+    /// other children, v-else-if and v-else branches follow the subtree of their v-if element.
+    pub fn iter(&self) -> impl Iterator<Item = Rc<RefCell<ASTNode>>> + '_ {
+        let mut stack: Vec<Rc<RefCell<ASTNode>>> = self
+            .wrapper
+            .borrow()
+            .children
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+
+        std::iter::from_fn(move || {
+            let node_ptr = stack.pop()?;
+            {
+                let node = node_ptr.borrow();
+                let branches = node
+                    .el
+                    .if_conditions
+                    .iter()
+                    .flatten()
+                    .filter(|condition| condition.block_id != node.id)
+                    .filter_map(|condition| self.get(condition.block_id).cloned());
+                let scoped_slots = node.el.scoped_slots.iter().flat_map(|slots| slots.values());
+
+                let pending: Vec<Rc<RefCell<ASTNode>>> = scoped_slots
+                    .chain(node.children.iter())
+                    .cloned()
+                    .chain(branches)
+                    .collect();
+                stack.extend(pending.into_iter().rev());
+            }
+
+            Some(node_ptr)
+        })
+    }
+
+    /// Every node of the tree ordered by id, starting with the wrapper node (id 0).
+    pub fn all_nodes(&self) -> Vec<Rc<RefCell<ASTNode>>> {
+        let mut nodes: Vec<Rc<RefCell<ASTNode>>> = self.nodes.values().cloned().collect();
+        nodes.sort_by_key(|node| node.borrow().id);
+
+        nodes
+    }
+}
+
+// raw attribute names of an element, split by whether processing consumed them
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AttrDisposition {
+    pub consumed: Vec<String>,
+    pub kept: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -294,52 +541,42 @@ impl ASTNode {
             return;
         }
 
-        let attrs_opt = &self.el.token.attrs;
-        let mut attributes: Vec<(String, Option<String>, QuoteType)> = vec![];
-
-        if let Some(attrs) = attrs_opt {
-            if attrs.is_empty() {
-                // non root node in pre blocks with no attributes
-                self.el.plain = true;
-
-                return;
-            }
+        let attrs = self.el.token.attrs.as_ref().unwrap();
+        if attrs.is_empty() {
+            // non root node in pre blocks with no attributes
+            self.el.plain = true;
 
-            for (attr_name, attr_value_quote) in attrs.iter() {
-                attributes.push(if let Some(attr_entry) = attr_value_quote {
-                    (
-                        attr_name.to_string(),
-                        Some(attr_entry.0.to_string()),
-                        attr_entry.1,
-                    )
-                } else {
-                    (attr_name.to_string(), None, QuoteType::NoValue)
-                });
-            }
+            return;
         }
 
+        // copied verbatim, directives are not parsed inside v-pre
+        let attributes: Vec<(String, Option<String>, QuoteType)> = self
+            .attr_names_in_source_order()
+            .into_iter()
+            .map(|attr_name| match attrs.get(attr_name.as_str()).unwrap() {
+                Some(attr_entry) => (attr_name, Some(attr_entry.0.to_string()), attr_entry.1),
+                None => (attr_name, None, QuoteType::NoValue),
+            })
+            .collect();
+
         for (attr_name, attr_value, attr_quotes) in attributes {
-            self.insert_into_attrs(&attr_name, attr_value, attr_quotes, false);
+            self.insert_into_attrs(&attr_name, attr_value, attr_quotes, false, true);
         }
     }
 
     pub fn process_for(&mut self) {
         let val = self.get_and_remove_attr(&UC_V_FOR, false);
         if let Some(entry) = val {
-            if let Some(val) = entry.value {
-                let result_option = self.parse_for(&val);
+            let exp = entry.value.unwrap_or_default();
 
-                if let Some(result) = result_option {
-                    self.el.alias = Some(result.alias);
-                    self.el.for_value = Some(result.for_value);
-                    self.el.iterator1 = result.iterator1;
-                    self.el.iterator2 = result.iterator2;
-
-                    return;
-                }
+            if let Some(result) = self.parse_for(&exp) {
+                self.el.alias = Some(result.alias);
+                self.el.for_value = Some(result.for_value);
+                self.el.iterator1 = result.iterator1;
+                self.el.iterator2 = result.iterator2;
+            } else {
+                self.warn_with_range(&format!("Invalid v-for expression: {}", exp.trim()));
             }
-
-            self.warn.call("Invalid v-for expression");
         }
     }
 
@@ -381,7 +618,8 @@ impl ASTNode {
         let vif_optional = self.get_and_remove_attr(&UC_V_IF, false);
 
         if let Some(vif_value) = vif_optional {
-            if let Some(vif_value) = vif_value.value {
+            // an empty expression is treated the same as a missing one
+            if let Some(vif_value) = vif_value.value.filter(|val| !val.trim().is_empty()) {
                 let new_exp = Some(vif_value);
                 self.el.if_val = new_exp.clone();
                 self.add_if_condition(IfCondition {
@@ -389,7 +627,7 @@ impl ASTNode {
                     block_id: self.id,
                 });
             } else {
-                self.warn.call("Missing v-if expression.");
+                self.warn_with_range("Missing v-if expression.");
             }
         } else {
             let v_else_optional = self.get_and_remove_attr(&UC_V_ELSE, false);
@@ -403,13 +641,24 @@ impl ASTNode {
             let v_else_if_optional = self.get_and_remove_attr(&UC_V_ELSE_IF, false);
 
             if let Some(v_else_if_val) = v_else_if_optional {
-                if let Some(v_else_if_value) = v_else_if_val.value {
+                if let Some(v_else_if_value) =
+                    v_else_if_val.value.filter(|val| !val.trim().is_empty())
+                {
                     self.el.else_if_val = Some(v_else_if_value);
                 } else {
-                    self.warn.call("Missing v-else-if expression.");
+                    self.warn_with_range("Missing v-else-if expression.");
                 }
             }
         }
+
+        // v-for is processed first and has the higher precedence, the condition is
+        // evaluated separately for every item of the list
+        if self.is_dev && self.el.if_val.is_some() && self.el.for_value.is_some() {
+            self.warn_with_range(&format!(
+                "<{}>: v-if is used on the same element as v-for. v-for takes precedence, so the condition is evaluated for every item. Move the v-if to a wrapping <template> element or filter the list in a computed property instead.",
+                self.el.token.data
+            ));
+        }
     }
 
     pub fn process_once(&mut self) {
@@ -511,28 +760,32 @@ impl ASTNode {
     }
 
     pub fn get_binding_attr(&mut self, name: &'static str, get_static: bool) -> String {
-        let mut dynamic_value =
-            self.get_and_remove_attr_including_quotes(&(":".to_string() + name), false);
+        self.get_binding_attr_opt(name, get_static)
+            .unwrap_or_default()
+    }
 
-        if dynamic_value.is_none() {
-            dynamic_value =
-                self.get_and_remove_attr_including_quotes(&("v-bind:".to_string() + name), false);
-        }
-        if let Some(found_dynamic_value) = dynamic_value {
-            return parse_filters(&found_dynamic_value);
+    // None when the attribute is not present at all, Some("") when it is present but empty
+    pub fn get_binding_attr_opt(&mut self, name: &str, get_static: bool) -> Option<String> {
+        for dynamic_name in [":".to_string() + name, "v-bind:".to_string() + name] {
+            if let Some(dynamic_entry) = self.get_and_remove_attr(&dynamic_name, false) {
+                return Some(match dynamic_entry.value {
+                    Some(value) => {
+                        let (exp, filters) = parse_filters(&value);
+                        self.el.filters.extend(filters);
+                        exp
+                    }
+                    None => String::new(),
+                });
+            }
         }
         if get_static {
-            let static_value = self.get_and_remove_attr(&name, false);
-            if let Some(found_static_value) = static_value {
-                if let Some(value) = found_static_value.value {
-                    return value.to_string();
-                }
-                // value was found but it was empty asd
-                // TODO: Should it warn here?
+            // static values become string literals so they can be told apart from bindings
+            if let Some(static_entry) = self.get_and_remove_attr(name, false) {
+                return Some(to_js_string(&static_entry.value.unwrap_or_default()));
             }
         }
 
-        return String::new();
+        None
     }
 
     pub fn get_raw_binding_attr(&self, name: &'static str) -> Option<&Box<str>> {
@@ -583,7 +836,7 @@ impl ASTNode {
 
             // do not warn about single whitespace text nodes
             if is_dev && children[i].borrow().el.token.data.as_ref() != " " {
-                self.warn.call(&format!(
+                self.warn_with_range(&format!(
                     "text \"{}\" between v-if and v-else(-if) will be ignored.",
                     &children[i].borrow().el.token.data.trim()
                 ));
@@ -609,7 +862,7 @@ impl ASTNode {
                 });
             }
         } else if self.is_dev {
-            self.warn.call(&format!(
+            self.warn_with_range(&format!(
                 "v-{} used on element <{}> without corresponding v-if.",
                 match &self.el.else_if_val {
                     Some(else_if_val) => format!("else-if=\"{}\"", else_if_val),
@@ -620,8 +873,11 @@ impl ASTNode {
         }
     }
 
-    pub fn process_element(&mut self, tree: &ASTTree, options: &CompilerOptions) {
+    pub fn process_element(&mut self, tree: &mut ASTTree, options: &CompilerOptions) {
         self.process_key();
+        if options.auto_key_v_for {
+            self.inject_for_key();
+        }
 
         // determine whether this is a plain element after
         // removing structural attributes
@@ -630,17 +886,98 @@ impl ASTNode {
             && self.el.token.attrs.is_none();
 
         self.process_ref();
+        // resolve the component first so that slot checks see the correct is_maybe_component
+        self.process_component();
+        if let Some(component_name) = &options.component_name {
+            self.el.is_self_reference = self.el.component.is_none()
+                && (self.el.token.data.eq_ignore_ascii_case(component_name)
+                    || self
+                        .el
+                        .token
+                        .data
+                        .eq_ignore_ascii_case(&to_hyphen_case(component_name)));
+        }
+        if let Some(resolve_component) = options.resolve_component {
+            if self.el.component.is_none() && !is_reserved_tag(&self.el.token.data) {
+                if let Some(resolved_tag) = resolve_component(&self.el.token.data) {
+                    self.el.token.data = resolved_tag.into_boxed_str();
+                }
+            }
+        }
         self.process_slot_content(tree, options);
         self.process_slot_outlet();
-        self.process_component();
 
         if let Some(registered_modules) = &options.modules {
             for module in registered_modules.iter() {
                 module.transform_node(self, &options);
             }
         }
+        if let Some(transforms) = &options.transforms {
+            for transform in transforms {
+                transform.transform(self, tree, options);
+            }
+        }
 
         self.process_attrs(options);
+        self.process_content_directives(tree);
+    }
+
+    // v-html and v-text replace the content of the element, its children would never be rendered
+    fn process_content_directives(&mut self, tree: &mut ASTTree) {
+        if self.children.is_empty() {
+            return;
+        }
+        let Some(directive) = self.el.directives.as_ref().and_then(|directives| {
+            directives
+                .iter()
+                .find(|directive| directive.name == "html" || directive.name == "text")
+        }) else {
+            return;
+        };
+
+        if self.is_dev {
+            let message = format!(
+                "{}=\"{}\": v-{} replaces the content of <{}>, its children will be ignored.",
+                directive.raw_name,
+                directive.value.as_deref().unwrap_or_default(),
+                directive.name,
+                self.el.token.data
+            );
+            self.warn_with_range(&message);
+        }
+        for child in std::mem::take(&mut self.children) {
+            tree.remove(child.borrow().id);
+        }
+    }
+
+    // runs the processing the parser does for an element on a node built outside of the parser:
+    // v-pre, the structural directives (v-for, v-if, v-once) and process_element.
+    // The children should already be attached since slot content is resolved from them.
+    // Pre transforms are not run as they may replace the node, the node is processed only once
+    pub fn process(&mut self, tree: &mut ASTTree, options: &CompilerOptions) {
+        if self.el.processed {
+            return;
+        }
+
+        self.process_pre();
+        if self.el.pre {
+            self.process_raw_attributes();
+        } else {
+            self.process_for();
+            self.process_if();
+            self.process_once();
+            self.process_element(tree, options);
+        }
+
+        self.el.processed = true;
+    }
+
+    fn resolve_slot_name(&mut self, binding: &AttrEntry) -> SlotName {
+        if self.is_dev && binding.name == "#" {
+            self.warn_with_range("v-slot shorthand syntax requires a slot name.");
+        }
+
+        get_slot_name(binding)
     }
 
     // handle <slot/> outlets
@@ -653,11 +990,8 @@ impl ASTNode {
             }
 
             if self.is_dev && self.el.key.is_some() {
-                println!(
-                    "`key` does not work on <slot> because slots are abstract outlets \
-                and can possibly expand into multiple elements. \
-                Use the key on a wrapping element instead. {}",
-                    self.get_raw_binding_attr("key").unwrap_or(&"".into())
+                self.warn_with_range(
+                    "`key` does not work on <slot> because slots are abstract outlets and can possibly expand into multiple elements. Use the key on a wrapping element instead.",
                 );
             }
         }
@@ -666,7 +1000,14 @@ impl ASTNode {
     pub fn process_component(&mut self) {
         let binding = self.get_binding_attr("is", true);
 
-        if !binding.is_empty() {
+        // <template> renders its children only, it cannot be turned into a component
+        if !binding.is_empty() && self.el.token.data.eq_ignore_ascii_case("template") {
+            if self.is_dev {
+                self.warn_with_range(
+                    "<template> cannot be used with the \"is\" attribute, it is ignored. Use <component :is> instead.",
+                );
+            }
+        } else if !binding.is_empty() {
             self.el.component = Some(binding);
         }
 
@@ -675,15 +1016,31 @@ impl ASTNode {
         }
     }
 
-    pub fn process_key(&mut self) {
-        let exp = self.get_binding_attr(&UC_KEY, true);
+    // Non-standard: keys v-for elements without an explicit key by their iteration index.
+    // <template> cannot be keyed, so it is left untouched.
+    pub fn inject_for_key(&mut self) {
+        if self.el.for_value.is_none()
+            || self.el.key.is_some()
+            || self.el.token.data.eq_ignore_ascii_case("template")
+        {
+            return;
+        }
+
+        let index = self
+            .el
+            .iterator1
+            .get_or_insert_with(|| "$index".to_string())
+            .clone();
+        self.el.key = Some(index);
+    }
 
-        if !exp.is_empty() {
+    pub fn process_key(&mut self) {
+        if let Some(exp) = self.get_binding_attr_opt(&UC_KEY, true) {
             if self.is_dev {
                 if self.el.token.data.eq_ignore_ascii_case("template") {
                     let opt = self.get_raw_binding_attr(&UC_KEY);
 
-                    self.warn.call(
+                    self.warn_with_range(
                         &format!("<template> cannot be keyed. Place the key on real elements instead. key was {}",
                             if opt.is_some() { opt.as_deref().unwrap() } else { "" }
                         ),
@@ -695,6 +1052,32 @@ impl ASTNode {
                 let has_iterator_2 =
                     self.el.iterator2.is_some() && self.el.iterator2.as_ref().unwrap().eq(&exp);
 
+                // a static key is never compared against anything outside of v-for, v-if chains and transitions
+                if self.has_raw_attr(&UC_KEY)
+                    && !self.el.token.data.eq_ignore_ascii_case("template")
+                    && !self.el.token.data.eq_ignore_ascii_case("slot")
+                    && self.el.for_value.is_none()
+                    && self.el.if_val.is_none()
+                    && self.el.else_if_val.is_none()
+                    && !self.el.is_else
+                    && !self
+                        .parent
+                        .as_ref()
+                        .and_then(|parent| parent.upgrade())
+                        .is_some_and(|parent| {
+                            let parent_tag = &parent.borrow().el.token.data;
+
+                            parent_tag.eq_ignore_ascii_case("transition")
+                                || parent_tag.eq_ignore_ascii_case("transition-group")
+                        })
+                {
+                    self.warn_with_range(&format!(
+                        "key=\"{}\" on <{}> has no effect, keys are only needed on elements rendered with v-for, v-if or inside a <transition>.",
+                        self.get_raw_attr_value(&UC_KEY).map(|key| key.as_ref()).unwrap_or_default(),
+                        self.el.token.data
+                    ));
+                }
+
                 if self.el.for_value.is_some() {
                     if has_iterator_1 || has_iterator_2 {
                         {
@@ -707,7 +1090,7 @@ impl ASTNode {
                                     .eq_ignore_ascii_case("transition-group")
                                 {
                                     // getRawBindingAttr(el, 'key'),
-                                    self.warn.call(
+                                    self.warn_with_range(
                                         "Do not use v-for index as key on <transition-group> children,\nthis is the same as not using keys.",
                                     );
                                 }
@@ -720,17 +1103,23 @@ impl ASTNode {
         }
     }
     fn process_ref(&mut self) {
-        let ref_option = self.get_and_remove_attr("ref", false);
-
-        if let Some(ref_entry) = ref_option {
-            if let Some(ref_value) = ref_entry.value {
-                self.el.ref_val = Some(ref_value);
-                self.el.ref_in_for = self.check_in_for();
+        if self.is_dev && self.get_raw_binding_attr("ref").is_some() {
+            if self.el.token.data.eq_ignore_ascii_case("template") {
+                self.warn_with_range(
+                    "<template> cannot have a ref. Place the ref on real elements instead.",
+                );
+            } else if self.el.token.data.eq_ignore_ascii_case("slot") {
+                self.warn_with_range("`ref` does not work on <slot> because slots are abstract outlets and can possibly expand into multiple elements. Use the ref on a wrapping element instead.");
             }
         }
+
+        if let Some(ref_value) = self.get_binding_attr_opt("ref", true) {
+            self.el.ref_val = Some(ref_value);
+            self.el.ref_in_for = self.check_in_for();
+        }
     }
 
-    pub fn process_slot_content(&mut self, tree: &ASTTree, options: &CompilerOptions) {
+    pub fn process_slot_content(&mut self, tree: &mut ASTTree, options: &CompilerOptions) {
         let is_dev = self.is_dev;
         let mut slot_scope_entry_value: Option<String> = None;
 
@@ -739,7 +1128,7 @@ impl ASTNode {
 
             if let Some(slot_scope_val) = slot_scope {
                 if is_dev {
-                    self.warn.call("the \"scope\" attribute for scoped slots have been deprecated and replaced by \"slot-scope\" since 2.5. The new \"slot-scope\" attribute can also be used on plain elements in addition to <template> to denote scoped slots.");
+                    self.warn_with_range("the \"scope\" attribute for scoped slots have been deprecated and replaced by \"slot-scope\" since 2.5. The new \"slot-scope\" attribute can also be used on plain elements in addition to <template> to denote scoped slots.");
                 }
 
                 slot_scope_entry_value = slot_scope_val.value;
@@ -759,33 +1148,26 @@ impl ASTNode {
                 self.el.slot_scope = slot_scope_entry.value;
 
                 if is_dev && self.has_raw_attr("v-for") {
-                    self.warn.call("Ambiguous combined usage of slot-scope and v-for on <{TODO}> (v-for takes higher priority). Use a wrapper <template> for the scoped slot to make it clearer.");
+                    self.warn_with_range(&format!("Ambiguous combined usage of slot-scope and v-for on <{}> (v-for takes higher priority). Use a wrapper <template> for the scoped slot to make it clearer.", self.el.token.data));
                 }
             }
         }
 
         // slot="xxx"
-        let slot_target = self.get_and_remove_attr("slot", false);
-        if let Some(ref slot_target_entry) = slot_target {
-            self.el.slot_target = if let Some(slot_target_value) = &slot_target_entry.value {
-                Some(slot_target_value.to_string())
-            } else {
-                Some("\"default\"".to_string())
-            };
-
+        if let Some(slot_target) = self.get_binding_attr_opt("slot", true) {
             self.el.slot_target_dynamic =
-                self.has_raw_attr("slot") || self.has_raw_attr("v-bind:slot");
+                self.has_raw_attr(":slot") || self.has_raw_attr("v-bind:slot");
             // preserve slot as an attribute for native shadow DOM compat
             // only for non-scoped slots.
-            if !self.el.token.data.eq_ignore_ascii_case("template") && !self.el.slot_scope.is_some()
+            if !self.el.token.data.eq_ignore_ascii_case("template") && self.el.slot_scope.is_none()
             {
-                self.insert_into_attrs(
-                    "slot",
-                    slot_target_entry.value.clone(),
-                    QuoteType::NoValue,
-                    false,
-                );
+                self.add_attr("slot", Some(slot_target.clone()), false);
             }
+            self.el.slot_target = Some(if slot_target.is_empty() || slot_target == "\"\"" {
+                "\"default\"".to_string()
+            } else {
+                slot_target
+            });
         }
 
         // 2.6 v-slot syntax
@@ -799,21 +1181,22 @@ impl ASTNode {
                         let slot_scope = self.el.slot_scope.clone();
 
                         if slot_target.is_some() || slot_scope.is_some() {
-                            self.warn.call("Unexpected mixed usage of different slot syntaxes. (slot-target, slot-scope)");
+                            self.warn_with_range("Unexpected mixed usage of different slot syntaxes. (slot-target, slot-scope)");
                         }
                         if let Some(parent) = self
                             .parent
                             .as_ref()
                             .and_then(|parent_weak| parent_weak.upgrade())
                         {
-                            if parent.borrow().is_maybe_component() {
-                                self.warn.call("<template v-slot> can only appear at the root level inside the receiving component.");
+                            if !parent.borrow().is_maybe_component() {
+                                self.warn_with_range("<template v-slot> can only appear at the root level inside the receiving component.");
                             }
                         }
                     }
-                    let slot_name = get_slot_name(&slot_binding_attr);
+                    let slot_name = self.resolve_slot_name(&slot_binding_attr);
                     self.el.slot_target = Some(slot_name.name);
                     self.el.slot_target_dynamic = slot_name.dynamic;
+                    self.el.v_slot = true;
                     self.el.slot_scope = Some(if slot_binding_attr.value.is_some() {
                         slot_binding_attr.value.unwrap()
                     } else {
@@ -826,16 +1209,18 @@ impl ASTNode {
                 if let Some(slot_binding_attr) = slot_binding {
                     if is_dev {
                         if !self.is_maybe_component() {
-                            self.warn
-                                .call("v-slot can only be used on components or <template>.")
+                            self.warn_with_range(
+                                "v-slot can only be used on components or <template>.",
+                            )
                         }
                         if self.el.slot_scope.is_some() || self.el.slot_target.is_some() {
-                            self.warn.call("Unexpected mixed usage of different slot syntaxes. (slot-scope, slot)");
+                            self.warn_with_range("Unexpected mixed usage of different slot syntaxes. (slot-scope, slot)");
                         }
                         if self.el.scoped_slots.is_some() {
-                            self.warn.call("To avoid scope ambiguity, the default slot should also use <template> syntax when there are other named slots.");
+                            self.warn_with_range("To avoid scope ambiguity, the default slot should also use <template> syntax when there are other named slots.");
                         }
                     }
+                    let slot_name = self.resolve_slot_name(&slot_binding_attr);
                     let slots = if self.el.scoped_slots.is_some() {
                         self.el.scoped_slots.as_mut().unwrap()
                     } else {
@@ -843,7 +1228,6 @@ impl ASTNode {
                         self.el.scoped_slots.as_mut().unwrap()
                     };
 
-                    let slot_name = get_slot_name(&slot_binding_attr);
                     let slot_container = tree.create(
                         create_ast_element(
                             Token {
@@ -862,9 +1246,11 @@ impl ASTNode {
 
                     slot_container_node.el.slot_target = Some(slot_name.name.to_string());
                     slot_container_node.el.slot_target_dynamic = slot_name.dynamic;
+                    slot_container_node.el.v_slot = true;
 
-                    // Convert self to a Weak reference
-                    let parent = tree.get(self.id).cloned().unwrap();
+                    // children of the component now belong to the slot container
+                    let parent = Rc::downgrade(&slot_container);
+                    let parent_id = slot_container_node.id;
 
                     slot_container_node.children = self
                         .children
@@ -873,7 +1259,8 @@ impl ASTNode {
                         .filter_map(|child_rc| {
                             let mut child = child_rc.borrow_mut();
                             if child.el.slot_scope.is_none() {
-                                child.parent = Some(Rc::downgrade(&parent));
+                                child.parent = Some(parent.clone());
+                                child.parent_id = parent_id;
                                 Some(Rc::clone(&child_rc))
                             } else {
                                 None
@@ -904,6 +1291,7 @@ impl ASTNode {
         value: Option<String>,
         quote_type: QuoteType,
         is_dynamic: bool,
+        literal: bool,
     ) {
         if self.el.ignored.contains(key) {
             return;
@@ -916,6 +1304,7 @@ impl ASTNode {
             value: value.clone(),
             dynamic: is_dynamic,
             quote_type,
+            literal,
         };
 
         if value.is_some() {
@@ -950,6 +1339,7 @@ impl ASTNode {
             value,
             dynamic: is_dynamic,
             quote_type,
+            literal: false,
         };
         self.el.props.push(item);
     }
@@ -983,46 +1373,220 @@ impl ASTNode {
             return;
         }
 
-        // TODO: Finish variables in warning
-        let warn_text = r#""<{el.tag} v-model="${value}">
-You are binding v-model directly to a v-for iteration alias.
-This will not be able to modify the v-for source array because
-writing to the alias is like modifying a function local variable.
-Consider using an array of objects and use v-model on an object property instead.""#;
-
-        if self.el.for_value.is_some()
-            && is_some_and_ref(&self.el.alias, |alias| alias.eq_ignore_ascii_case(val))
-        {
-            self.warn.call(warn_text);
-            return;
-        }
+        let val = val.trim();
+        // the alias and the iterators are function locals of the v-for render function,
+        // identifiers are case sensitive in javascript
+        let is_iteration_variable = |el: &ASTElement| {
+            el.for_value.is_some()
+                && el
+                    .alias
+                    .iter()
+                    .flat_map(|alias| pattern_identifiers(alias))
+                    .chain(el.iterator1.iter().chain(&el.iterator2).cloned())
+                    .any(|name| name == val)
+        };
 
+        let mut binds_iteration_variable = is_iteration_variable(&self.el);
         let mut current_node = self
             .parent
             .as_ref()
             .and_then(|parent_weak| parent_weak.upgrade());
 
-        while let Some(node) = current_node {
-            if node.borrow().el.for_value.is_some()
-                && is_some_and_ref(&node.borrow().el.alias, |alias| {
-                    alias.eq_ignore_ascii_case(val)
-                })
-            {
-                self.warn.call(warn_text);
-            }
+        while let (false, Some(node)) = (binds_iteration_variable, current_node) {
+            binds_iteration_variable = is_iteration_variable(&node.borrow().el);
             current_node = node
                 .borrow()
                 .parent
                 .as_ref()
                 .and_then(|parent_weak| parent_weak.upgrade());
         }
+
+        if binds_iteration_variable {
+            self.warn_with_range(&format!(
+                "<{} v-model=\"{}\">: You are binding v-model directly to a v-for iteration alias. This will not be able to modify the v-for source array because writing to the alias is like modifying a function local variable. Consider using an array of objects and use v-model on an object property instead.",
+                self.el.token.data, val
+            ));
+        }
+    }
+
+    // the value of a .sync binding is assigned to, it has to be a property path.
+    // Checked as written, a filtered value is not assignable either
+    fn check_sync_target(&mut self, raw_name: &str) {
+        let Some(raw_value) = self.get_raw_attr_value(raw_name) else {
+            return;
+        };
+        if is_assignable(raw_value) {
+            return;
+        }
+
+        let message = format!(
+            "{}=\"{}\": the .sync modifier requires an assignable expression such as a property path like a.b or a[b].",
+            raw_name,
+            raw_value.trim()
+        );
+        self.warn_with_range(&message);
+    }
+
+    // warnings about this element carry the range of its start tag, see CompilerOptions::output_source_range
+    pub fn warn_with_range(&mut self, msg: &str) {
+        self.warn.call_with_range(msg, self.el.range);
+    }
+
+    // v-model needs a form element or a component to bind to
+    pub fn check_model_target(&mut self, val: &str) {
+        if !self.is_dev {
+            return;
+        }
+
+        let tag = &*self.el.token.data;
+        if self.el.component.is_some() || !is_reserved_tag(tag) {
+            return;
+        }
+
+        if tag.eq_ignore_ascii_case("input") {
+            let is_file = self
+                .el
+                .token
+                .attrs
+                .as_ref()
+                .and_then(|attrs| attrs.get("type"))
+                .and_then(|type_value| type_value.as_ref())
+                .is_some_and(|type_value| type_value.0.eq_ignore_ascii_case("file"));
+            if is_file {
+                self.warn_with_range(&format!(
+                    "<{} v-model=\"{}\" type=\"file\">:\nFile inputs are read only. Use a v-on:change listener instead.",
+                    tag, val
+                ));
+            }
+        } else if !tag.eq_ignore_ascii_case("select") && !tag.eq_ignore_ascii_case("textarea") {
+            self.warn_with_range(&format!(
+                "<{} v-model=\"{}\">: v-model is not supported on this element type. If you are working with contenteditable, it's recommended to wrap a library dedicated for that purpose inside a custom component.",
+                tag, val
+            ));
+        }
+    }
+
+    // true when the element or one of its ancestors only renders conditionally (v-if chain or v-for),
+    // a self referencing component without such a guard recurses without end
+    pub fn is_recursion_guarded(&self) -> bool {
+        if self.el.if_val.is_some()
+            || self.el.else_if_val.is_some()
+            || self.el.is_else
+            || self.el.for_value.is_some()
+        {
+            return true;
+        }
+
+        let mut current_node = self.parent.as_ref().and_then(|parent| parent.upgrade());
+        while let Some(node) = current_node {
+            let node = node.borrow();
+            if node.el.if_val.is_some()
+                || node.el.else_if_val.is_some()
+                || node.el.is_else
+                || node.el.for_value.is_some()
+            {
+                return true;
+            }
+            current_node = node.parent.as_ref().and_then(|parent| parent.upgrade());
+        }
+
+        false
+    }
+
+    // which template attributes were consumed as structural (ignored) and which are left for
+    // output, both in source order. Attributes written back to the token while processing are
+    // not part of the template and not reported
+    pub fn attr_disposition(&self) -> AttrDisposition {
+        let (consumed, kept) = self
+            .el
+            .attr_names
+            .iter()
+            .cloned()
+            .partition(|name| self.el.ignored.contains(name.as_str()));
+
+        AttrDisposition { consumed, kept }
+    }
+
+    // the children normalization the runtime needs, else-branches of a child are looked up in the tree
+    pub fn compute_normalization_type(&self, tree: &ASTTree) -> NormalizationType {
+        let mut res = NormalizationType::None;
+
+        for child in &self.children {
+            let child = child.borrow();
+            if child.el.kind != ASTElementKind::Element {
+                continue;
+            }
+
+            let branches: Vec<Rc<RefCell<ASTNode>>> = child
+                .el
+                .if_conditions
+                .iter()
+                .flatten()
+                .filter(|condition| condition.block_id != child.id)
+                .filter_map(|condition| tree.get(condition.block_id).cloned())
+                .collect();
+
+            if child.needs_normalization()
+                || branches
+                    .iter()
+                    .any(|branch| branch.borrow().needs_normalization())
+            {
+                return NormalizationType::Full;
+            }
+            if child.is_maybe_component()
+                || branches
+                    .iter()
+                    .any(|branch| branch.borrow().is_maybe_component())
+            {
+                res = NormalizationType::Simple;
+            }
+        }
+
+        res
+    }
+
+    fn needs_normalization(&self) -> bool {
+        self.el.for_value.is_some()
+            || self.el.token.data.eq_ignore_ascii_case("template")
+            || self.el.token.data.eq_ignore_ascii_case("slot")
     }
 
-    // TODO: Finish this
     pub fn is_maybe_component(&self) -> bool {
-        self.el.component.is_some() || self.has_raw_attr("is") || self.has_raw_attr("v-bind:is")
-        // !(self.el.token.attrs.attrsMap.is ? isReservedTag(el.attrsMap.is) : isReservedTag(el.tag))
+        // "is" has no effect on <template>
+        if self.el.token.data.eq_ignore_ascii_case("template") {
+            return false;
+        }
+
+        self.el.component.is_some()
+            || self.has_raw_attr(":is")
+            || self.has_raw_attr("v-bind:is")
+            || !match self.get_raw_attr_value("is") {
+                Some(is_value) => is_reserved_tag(is_value),
+                None => is_reserved_tag(&self.el.token.data),
+            }
+    }
+    // attribute names in source order, attributes added by transforms come last
+    fn attr_names_in_source_order(&self) -> Vec<String> {
+        let Some(attrs) = &self.el.token.attrs else {
+            return vec![];
+        };
+
+        let mut names: Vec<String> = self
+            .el
+            .attr_names
+            .iter()
+            .filter(|name| attrs.contains_key(name.as_str()))
+            .cloned()
+            .collect();
+        for name in attrs.keys() {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+
+        names
     }
+
     pub fn process_attrs(&mut self, options: &CompilerOptions) {
         if self.el.token.attrs.is_none() {
             return;
@@ -1030,8 +1594,16 @@ Consider using an array of objects and use v-model on an object property instead
 
         // TODO: Get rid off this clone
         let attrs = self.el.token.attrs.clone().unwrap();
-        for (orig_name, orig_val) in attrs.iter().rev() {
-            self.process_attr(&orig_name, &orig_val, options);
+
+        let names = self.attr_names_in_source_order();
+
+        for name in names {
+            // already consumed by the structural directives and modules
+            if self.el.ignored.contains(name.as_str()) {
+                continue;
+            }
+            let orig_val = attrs.get(name.as_str()).unwrap();
+            self.process_attr(&name, orig_val, options);
         }
     }
 
@@ -1074,16 +1646,22 @@ Consider using an array of objects and use v-model on an object property instead
                 // v-bind
                 name_str = BIND_RE.replace_all(&name_str, "").to_string();
                 if let Some(val) = value {
-                    value = Some((Box::from(parse_filters(&val)), val.1));
+                    let (exp, filters) = parse_filters(&val.0);
+                    self.el.filters.extend(filters);
+                    value = Some((Box::from(exp), val.1));
                 }
 
                 let is_dynamic = DYNAMIC_ARG_RE.is_match(&name_str);
                 if is_dynamic {
                     name_str = name_str[1..name_str.len() - 1].to_string();
+                } else if self.el.ns.as_deref() == Some("svg") {
+                    if let Some(svg_name) = svg_attr_name(&name_str) {
+                        name_str = svg_name.to_string();
+                    }
                 }
 
-                if self.is_dev && value.as_ref().is_some_and(|v| v.0.trim().is_empty()) {
-                    self.warn.call(&format!(
+                if self.is_dev && value.as_ref().is_none_or(|v| v.0.trim().is_empty()) {
+                    self.warn_with_range(&format!(
                         "The value for a v-bind expression cannot be empty. Found in \"v-bind:{}\"",
                         name_str
                     ));
@@ -1091,6 +1669,8 @@ Consider using an array of objects and use v-model on an object property instead
 
                 if let Some(ref modifiers) = modifiers_option.as_ref() {
                     if modifiers.contains("prop") && !is_dynamic {
+                        // dom properties are camel cased, e.g. :text-content.prop
+                        name_str = to_camel(&name_str);
                         if name_str.eq_ignore_ascii_case("innerHtml") {
                             name_str = "innerHTML".to_string();
                         }
@@ -1098,12 +1678,19 @@ Consider using an array of objects and use v-model on an object property instead
                     if modifiers.contains("camel") && !is_dynamic {
                         name_str = to_camel(&name_str);
                     }
+                    if modifiers.contains("number") && options.compat_number_modifier {
+                        self.el.number_bindings.push(name_str.clone());
+                    }
                     if modifiers.contains("sync") {
+                        if self.is_dev {
+                            self.check_sync_target(&raw_name);
+                        }
                         let sync_gen = if value.is_some() {
                             gen_assignment_code(value.as_ref().unwrap(), "$event")
                         } else {
                             "".to_string()
                         };
+                        let range = self.el.attr_ranges.get(raw_name.as_str()).copied();
                         if !is_dynamic {
                             let camel_case_name = to_camel(&name_str);
                             let hyphen_case_name = to_hyphen_case(&name_str);
@@ -1114,6 +1701,7 @@ Consider using an array of objects and use v-model on an object property instead
                                 None,
                                 false,
                                 false,
+                                range,
                             );
 
                             if hyphen_case_name != camel_case_name {
@@ -1123,6 +1711,7 @@ Consider using an array of objects and use v-model on an object property instead
                                     None,
                                     false,
                                     false,
+                                    range,
                                 );
                             }
                         } else {
@@ -1133,6 +1722,7 @@ Consider using an array of objects and use v-model on an object property instead
                                 None,
                                 false,
                                 true,
+                                range,
                             );
                         }
                     }
@@ -1154,38 +1744,69 @@ Consider using an array of objects and use v-model on an object property instead
                 {
                     self.insert_into_props(&name_str, attr_value.0, attr_value.1, is_dynamic);
                 } else {
-                    self.insert_into_attrs(&name_str, attr_value.0, attr_value.1, is_dynamic);
+                    self.insert_into_attrs(
+                        &name_str,
+                        attr_value.0,
+                        attr_value.1,
+                        is_dynamic,
+                        false,
+                    );
                 }
-            } else if ON_RE.is_match(&name_str) {
-                // v-on
-                let attr_value: Box<str>;
-                if let Some(val) = value {
-                    attr_value = val.0;
-                } else {
-                    self.warn.call("TODO: ignoring v-on without value");
-                    return;
+            } else if name_str.eq_ignore_ascii_case("v-bind") {
+                // v-bind="object", the object is merged into the element data at runtime
+                let has_modifier = |modifier: &str| {
+                    is_some_and_ref(&modifiers_option, |modifiers| modifiers.contains(modifier))
+                };
+
+                self.el.v_bind_object = Some(VBindObject {
+                    value: value.map(|val| val.0.to_string()).unwrap_or_default(),
+                    prop: has_modifier("prop"),
+                    sync: has_modifier("sync"),
+                });
+                self.el.plain = false;
+            } else if name_str.eq_ignore_ascii_case("v-on") {
+                // v-on="object", every key of the object is an event name
+                if self.is_dev && modifiers_option.is_some() {
+                    self.warn_with_range("v-on without argument does not support modifiers.");
                 }
 
+                self.el.v_on_object = Some(value.map(|val| val.0.to_string()).unwrap_or_default());
+                self.el.plain = false;
+            } else if ON_RE.is_match(&name_str) {
+                // v-on
                 name_str = ON_RE.replace_all(&name_str, "").to_string();
+
+                // a handler may be left out only when modifiers do the work, e.g. @submit.prevent
+                let attr_value: Box<str> = match value {
+                    Some(val) if !val.0.trim().is_empty() => val.0,
+                    _ if modifiers_option.is_some() => Box::from(""),
+                    _ => {
+                        if self.is_dev {
+                            self.warn_with_range(&format!(
+                                "The value for a v-on expression cannot be empty. Found in \"v-on:{}\"",
+                                name_str
+                            ));
+                        }
+                        return;
+                    }
+                };
                 let is_dynamic = DYNAMIC_ARG_RE.is_match(&name_str);
                 if is_dynamic {
                     name_str = name_str[1..name_str.len() - 1].to_string();
                 }
-                self.add_handler(&name_str, &attr_value, modifiers_option, false, is_dynamic);
+                let range = self.el.attr_ranges.get(raw_name.as_str()).copied();
+                let modifiers =
+                    parse_ordered_modifiers(dir_regex.replace_all(&raw_name, "").as_ref());
+                self.add_handler(
+                    &name_str,
+                    &attr_value,
+                    Some(modifiers),
+                    false,
+                    is_dynamic,
+                    range,
+                );
             } else {
-                let attr_value: Option<String>;
-                if let Some(val) = value {
-                    attr_value = Some(val.0.to_string());
-                    self.insert_into_attrs(
-                        &name_str,
-                        Some(attr_value.clone().unwrap().to_string()),
-                        val.1,
-                        false,
-                    );
-                } else {
-                    attr_value = None;
-                    self.insert_into_attrs(&name_str, None, QuoteType::NoValue, false);
-                }
+                let attr_value = value.map(|val| val.0.to_string());
 
                 // normal directives
                 name_str = dir_regex.replace_all(&name_str, "").to_string();
@@ -1201,6 +1822,17 @@ Consider using an array of objects and use v-model on an object property instead
                         is_dynamic = true;
                     }
                 }
+                // v-show and v-model need an expression to show or bind to
+                if self.is_dev
+                    && (name_str.eq_ignore_ascii_case("show")
+                        || name_str.eq_ignore_ascii_case("model"))
+                    && attr_value.as_ref().is_none_or(|v| v.trim().is_empty())
+                {
+                    self.warn_with_range(&format!(
+                        "The value for a v-{} expression cannot be empty. Found in \"{}\"",
+                        name_str, raw_name
+                    ));
+                }
                 self.add_directive(
                     &name_str,
                     &raw_name,
@@ -1213,6 +1845,7 @@ Consider using an array of objects and use v-model on an object property instead
                     self.check_for_alias_model(
                         attr_value.clone().as_ref().unwrap_or(&String::new()),
                     );
+                    self.check_model_target(attr_value.as_deref().unwrap_or_default());
                 }
             }
         } else {
@@ -1223,17 +1856,22 @@ Consider using an array of objects and use v-model on an object property instead
             };
 
             // literal attribute
+            if self.el.ns.as_deref() == Some("svg") {
+                if let Some(svg_name) = svg_attr_name(&name_str) {
+                    name_str = svg_name.to_string();
+                }
+            }
             if self.is_dev {
                 if let Some(attr_val) = &attr_entry_opt.0 {
-                    let res = parse_text(&attr_val, &options.delimiters);
+                    let res = parse_text(attr_val, &options.delimiters, &options.helpers.to_string);
                     if res.is_some() {
-                        self.warn.call(
-                            &format!("{}=\"{}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{{ val }}\">, use <div :id=\"val\">.", &name, &attr_val)
+                        self.warn_with_range(
+                            &format!("{}=\"{}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{{{{ val }}}}\">, use <div :id=\"val\">.", &name, &attr_val)
                         );
                     }
                 }
             }
-            self.insert_into_attrs(&name_str, attr_entry_opt.0, attr_entry_opt.1, false);
+            self.insert_into_attrs(&name_str, attr_entry_opt.0, attr_entry_opt.1, false, true);
             // #6887 firefox doesn't update muted state if set via attribute
             // even immediately after element creation
             if self.el.component.is_none() && name == "muted" {
@@ -1250,30 +1888,67 @@ Consider using an array of objects and use v-model on an object property instead
         }
     }
 
+    pub fn add_attr(&mut self, name: &str, value: Option<String>, dynamic: bool) {
+        let item = AttrItem {
+            name: name.to_string(),
+            quote_type: if value.is_some() {
+                QuoteType::Double
+            } else {
+                QuoteType::NoValue
+            },
+            value,
+            dynamic,
+            literal: false,
+        };
+
+        if dynamic {
+            self.el.dynamic_attrs.push(item);
+        } else {
+            self.el.attrs.push(item);
+        }
+        self.el.plain = false;
+    }
+
+    pub fn add_prop(&mut self, name: &str, value: Option<String>, dynamic: bool) {
+        self.insert_into_props(
+            name,
+            value.clone(),
+            if value.is_some() {
+                QuoteType::Double
+            } else {
+                QuoteType::NoValue
+            },
+            dynamic,
+        );
+    }
+
     pub fn add_handler(
         &mut self,
         name: &str,
         value: &str,
-        modifiers: Option<UniCaseBTreeSet>,
+        modifiers: Option<Vec<String>>,
         important: bool,
         dynamic: bool,
+        range: Option<SourceRange>,
     ) {
-        let mut modifiers = modifiers.unwrap_or(UniCaseBTreeSet::new());
+        let mut modifiers = modifiers.unwrap_or_default();
         let mut name = name.to_string();
 
-        if cfg!(debug_assertions) {
-            if modifiers.get("prevent").is_some() && modifiers.get("passive").is_some() {
-                self.warn.call("passive and prevent can't be used together. Passive handler can't prevent default event.");
-            }
+        if self.is_dev && has_modifier(&modifiers, "prevent") && has_modifier(&modifiers, "passive")
+        {
+            self.warn_with_range("passive and prevent can't be used together. Passive handler can't prevent default event.");
         }
 
-        if modifiers.remove("right") {
+        // on other events .right is the arrow key modifier, e.g. @keyup.right. A dynamic
+        // event name is only known at runtime, the modifier stays for the key filter
+        if has_modifier(&modifiers, "right") {
             if dynamic {
                 name = format!("({})==='click'?'contextmenu':({})", name, name);
             } else if name == "click" {
+                remove_modifier(&mut modifiers, "right");
                 name = "contextmenu".to_string();
             }
-        } else if modifiers.remove("middle") {
+        } else if has_modifier(&modifiers, "middle") {
             if dynamic {
                 name = format!("({})==='click'?'mouseup':({})", name, name);
             } else if name == "click" {
@@ -1281,27 +1956,28 @@ Consider using an array of objects and use v-model on an object property instead
             }
         }
 
-        if modifiers.remove("capture") {
+        if remove_modifier(&mut modifiers, "capture") {
             name = prepend_modifier_marker('!', &name, dynamic);
         }
-        if modifiers.remove("once") {
+        if remove_modifier(&mut modifiers, "once") {
             name = prepend_modifier_marker('~', &name, dynamic);
         }
-        if modifiers.remove("passive") {
+        if remove_modifier(&mut modifiers, "passive") {
             name = prepend_modifier_marker('&', &name, dynamic);
         }
 
-        let events = (if modifiers.remove("native") {
+        let events = (if remove_modifier(&mut modifiers, "native") {
             &mut self.el.native_events
         } else {
             &mut self.el.events
         })
-        .get_or_insert(UniCaseBTreeMap::new());
+        .get_or_insert(BTreeMap::new());
 
         let new_handler = Handler {
             value: value.trim().to_string(),
             dynamic,
             modifiers,
+            range,
         };
 
         let handlers = events.entry(name).or_insert_with(Vec::new);
@@ -1341,17 +2017,99 @@ Consider using an array of objects and use v-model on an object property instead
 
 fn parse_modifiers(name: &str) -> Option<UniCaseBTreeSet> {
     let mut ret: Option<UniCaseBTreeSet> = None;
-    for cap in MODIFIER_RE.captures_iter(name) {
-        let matched_string = &cap[0];
-        if !matched_string.contains(']') {
-            ret.get_or_insert(UniCaseBTreeSet::new())
-                .insert(matched_string[1..].to_string());
-        }
+    for matched in modifier_matches(name) {
+        ret.get_or_insert(UniCaseBTreeSet::new())
+            .insert(matched.as_str()[1..].to_string());
     }
 
     return ret;
 }
 
+// the modifiers in the order they were written, a repeated one is listed once
+fn parse_ordered_modifiers(name: &str) -> Vec<String> {
+    let mut ret: Vec<String> = Vec::new();
+    for matched in modifier_matches(name) {
+        let modifier = &matched.as_str()[1..];
+        if !has_modifier(&ret, modifier) {
+            ret.push(modifier.to_string());
+        }
+    }
+
+    ret
+}
+
+fn has_modifier(modifiers: &[String], modifier: &str) -> bool {
+    modifiers.iter().any(|existing| existing == modifier)
+}
+
+fn remove_modifier(modifiers: &mut Vec<String>, modifier: &str) -> bool {
+    let len = modifiers.len();
+    modifiers.retain(|existing| existing != modifier);
+
+    modifiers.len() != len
+}
+
+// splits a destructuring pattern on `separator`, ignoring separators nested
+// in brackets, parens or string literals
+fn split_top_level(pattern: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+
+    for (index, chr) in pattern.char_indices() {
+        match (quote, chr) {
+            (Some(q), _) if chr == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(chr),
+            (None, '{' | '[' | '(') => depth += 1,
+            (None, '}' | ']' | ')') => depth -= 1,
+            (None, _) if chr == separator && depth == 0 => {
+                parts.push(&pattern[start..index]);
+                start = index + chr.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&pattern[start..]);
+
+    parts
+}
+
+/// Returns the local variables bound by a v-for alias, the alias itself or the
+/// identifiers of an object or array destructuring pattern such as
+/// `{ id, name: label }` or `[first, ...rest]`.
+pub fn pattern_identifiers(pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim();
+    let mut identifiers = Vec::new();
+
+    let (inner, is_object) = if pattern.starts_with('{') && pattern.ends_with('}') {
+        (&pattern[1..pattern.len() - 1], true)
+    } else if pattern.starts_with('[') && pattern.ends_with(']') {
+        (&pattern[1..pattern.len() - 1], false)
+    } else {
+        if !pattern.is_empty() {
+            identifiers.push(pattern.to_string());
+        }
+        return identifiers;
+    };
+
+    for element in split_top_level(inner, ',') {
+        let element = element.trim().trim_start_matches("...");
+        // drop the default value
+        let element = split_top_level(element, '=')[0];
+        // `key: target` binds the target in object patterns
+        let target = if is_object {
+            *split_top_level(element, ':').last().unwrap()
+        } else {
+            element
+        };
+        identifiers.extend(pattern_identifiers(target));
+    }
+
+    identifiers
+}
+
 #[derive(Debug)]
 pub struct SlotName {
     name: String,
@@ -1362,14 +2120,9 @@ pub fn get_slot_name(binding: &AttrEntry) -> SlotName {
     let mut name = SLOT_RE.replace_all(&binding.name, "").to_string();
 
     if name.is_empty() {
+        // a bare `#` keeps the empty name, see ASTNode::resolve_slot_name
         if !&binding.name.starts_with('#') {
             name = "default".to_string();
-        } else {
-            // TODO: warn in debug only
-            println!(
-                "v-slot shorthand syntax requires a slot name: {:?}",
-                binding
-            );
         }
     }
 
@@ -0,0 +1,1020 @@
+use crate::ast_tree::{
+    ASTElementKind, ASTNode, ASTTree, AttrItem, Handler, IfCondition, NormalizationType,
+    EMPTY_SLOT_SCOPE_TOKEN,
+};
+use crate::helpers::to_camel;
+use crate::trace::phase_span;
+use crate::warn_logger::{collecting_warn_logger, WarnLogger};
+use crate::{CompilerOptions, RenderHelpers};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
+
+lazy_static! {
+    static ref FN_EXP_RE: Regex =
+        Regex::new(r"^([\w$_]+|\([^)]*?\))\s*=>|^function(?:\s+[\w$]+)?\s*\(").unwrap();
+    static ref FN_INVOKE_RE: Regex = Regex::new(r"\([^)]*?\);*$").unwrap();
+    static ref SIMPLE_PATH_RE: Regex = Regex::new(
+        r#"^[A-Za-z_$][\w$]*(?:\.[A-Za-z_$][\w$]*|\['[^']*?'\]|\["[^"]*?"\]|\[\d+\]|\[[A-Za-z_$][\w$]*\])*$"#
+    )
+    .unwrap();
+}
+
+// key code and key names of the key modifiers the runtime `_k` helper resolves
+fn key_code(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "esc" => "27",
+        "tab" => "9",
+        "enter" => "13",
+        "space" => "32",
+        "up" => "38",
+        "left" => "37",
+        "right" => "39",
+        "down" => "40",
+        "delete" => "[8,46]",
+        _ => return None,
+    })
+}
+
+fn key_name(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "esc" => "[\"Esc\",\"Escape\"]",
+        "tab" => "\"Tab\"",
+        "enter" => "\"Enter\"",
+        "space" => "[\" \",\"Spacebar\"]",
+        "up" => "[\"Up\",\"ArrowUp\"]",
+        "left" => "[\"Left\",\"ArrowLeft\"]",
+        "right" => "[\"Right\",\"ArrowRight\"]",
+        "down" => "[\"Down\",\"ArrowDown\"]",
+        "delete" => "[\"Backspace\",\"Delete\",\"Del\"]",
+        _ => return None,
+    })
+}
+
+// the statements run before the handler for modifiers that guard or change the event
+fn modifier_code(modifier: &str) -> Option<String> {
+    let guard = |condition: &str| format!("if({})return null;", condition);
+
+    Some(match modifier {
+        "stop" => "$event.stopPropagation();".to_string(),
+        "prevent" => "$event.preventDefault();".to_string(),
+        "self" => guard("$event.target !== $event.currentTarget"),
+        "ctrl" => guard("!$event.ctrlKey"),
+        "shift" => guard("!$event.shiftKey"),
+        "alt" => guard("!$event.altKey"),
+        "meta" => guard("!$event.metaKey"),
+        "left" => guard("'button' in $event && $event.button !== 0"),
+        "middle" => guard("'button' in $event && $event.button !== 1"),
+        "right" => guard("'button' in $event && $event.button !== 2"),
+        _ => return None,
+    })
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CodegenResult {
+    pub render: String,
+    pub static_render_fns: Vec<String>,
+    // only collected when CompilerOptions::warn is not set
+    pub warnings: Vec<String>,
+}
+
+pub struct CodegenState<'a> {
+    pub options: &'a CompilerOptions,
+    pub tree: &'a ASTTree,
+    warn: RefCell<Box<dyn WarnLogger>>,
+    collected_warnings: Option<Rc<RefCell<Vec<String>>>>,
+    once_id: Cell<usize>,
+    static_render_fns: RefCell<Vec<String>>,
+}
+
+impl<'a> CodegenState<'a> {
+    pub fn new(tree: &'a ASTTree, options: &'a CompilerOptions) -> CodegenState<'a> {
+        let (warn, collected_warnings) = match &options.warn {
+            Some(warn) => (warn.clone(), None),
+            None => {
+                let (warn, warnings) = collecting_warn_logger();
+                (warn, Some(warnings))
+            }
+        };
+
+        CodegenState {
+            options,
+            tree,
+            warn: RefCell::new(warn),
+            collected_warnings,
+            once_id: Cell::new(0),
+            static_render_fns: RefCell::new(vec![]),
+        }
+    }
+}
+
+pub fn generate(tree: &ASTTree, options: &CompilerOptions) -> CodegenResult {
+    let _codegen_span = phase_span!("codegen");
+    let state = CodegenState::new(tree, options);
+
+    let wrapper = tree.wrapper.borrow();
+    let gen_root = |root: &Rc<RefCell<ASTNode>>| {
+        let root = root.borrow();
+
+        if root.el.token.data.eq_ignore_ascii_case("script") {
+            "null".to_string()
+        } else {
+            state.gen_element(&root)
+        }
+    };
+    let code = match wrapper.children.as_slice() {
+        [] => format!("{}(\"div\")", options.helpers.create_element),
+        [root] => gen_root(root),
+        // only CompilerOptions::allow_multiple_roots keeps more than one root
+        roots => format!(
+            "[{}]",
+            roots.iter().map(gen_root).collect::<Vec<_>>().join(",")
+        ),
+    };
+
+    CodegenResult {
+        render: format!("with(this){{return {}}}", code),
+        static_render_fns: state.static_render_fns.take(),
+        warnings: state
+            .collected_warnings
+            .map(|warnings| warnings.take())
+            .unwrap_or_default(),
+    }
+}
+
+// structural directives of an element that have already been turned into code
+#[derive(Debug, Default, Clone, Copy)]
+struct Processed {
+    static_processed: bool,
+    once_processed: bool,
+    for_processed: bool,
+    if_processed: bool,
+}
+
+impl CodegenState<'_> {
+    pub fn gen_element(&self, node: &ASTNode) -> String {
+        self.gen_element_processed(node, Processed::default())
+    }
+
+    fn gen_element_processed(&self, node: &ASTNode, processed: Processed) -> String {
+        let el = &node.el;
+
+        if el.static_root && !processed.static_processed {
+            return self.gen_static(node, processed);
+        }
+        if el.once && !processed.once_processed {
+            return self.gen_once(node, processed);
+        }
+        if el.for_value.is_some() && !processed.for_processed {
+            return self.gen_for(node, processed, &|node, processed| {
+                self.gen_element_processed(node, processed)
+            });
+        }
+        if el.if_val.is_some() && !processed.if_processed {
+            return self.gen_if(
+                node,
+                processed,
+                &|node, processed| self.gen_element_processed(node, processed),
+                &format!("{}()", self.options.helpers.create_empty),
+            );
+        }
+        if &*el.token.data == "template" && el.slot_target.is_none() && !el.pre {
+            return self
+                .gen_children(node, false)
+                .unwrap_or_else(|| "void 0".to_string());
+        }
+        if &*el.token.data == "slot" {
+            return self.gen_slot(node);
+        }
+
+        let data = if node.el.plain {
+            None
+        } else {
+            self.gen_data(node)
+        };
+        // the children of inline-template components are compiled into the data object
+        let children = if node.el.inline_template {
+            None
+        } else {
+            self.gen_children(node, true)
+        };
+
+        // components bound with "is" render the component expression instead of the tag
+        let tag = match &node.el.component {
+            Some(component) => component.clone(),
+            None => format!("'{}'", node.el.token.data),
+        };
+
+        format!(
+            "{}({}{}{})",
+            self.options.helpers.create_element,
+            tag,
+            match data {
+                Some(data) => format!(",{}", data),
+                None => String::new(),
+            },
+            match children {
+                Some(children) => format!(",{}", children),
+                None => String::new(),
+            }
+        )
+    }
+
+    // the vnode data object, None when there is nothing to put in it
+    pub fn gen_data(&self, node: &ASTNode) -> Option<String> {
+        let el = &node.el;
+        let helpers = &self.options.helpers;
+        let mut data = String::new();
+        // v-text and v-html render through dom props instead of a runtime directive
+        let mut directive_props: Vec<(&str, String)> = vec![];
+
+        // directives first, they may modify the other fields of the data object
+        if let Some(directives) = gen_directives(node, helpers, &mut directive_props) {
+            data += &directives;
+            data += ",";
+        }
+        if let Some(key) = &el.key {
+            data += &format!("key:{},", key);
+        }
+        if let Some(ref_val) = &el.ref_val {
+            data += &format!("ref:{},", ref_val);
+        }
+        if el.ref_in_for {
+            data += "refInFor:true,";
+        }
+        if el.pre {
+            data += "pre:true,";
+        }
+        // record the original tag name for components using "is" attribute
+        if el.component.is_some() {
+            data += &format!("tag:\"{}\",", el.token.data);
+        }
+
+        // module data generation functions (staticClass, class, staticStyle, style)
+        if let Some(modules) = &self.options.modules {
+            for module in modules {
+                if let Some(module_data) = module.gen_data(node) {
+                    data += &module_data;
+                }
+            }
+        }
+
+        if !el.attrs.is_empty() {
+            data += &format!(
+                "attrs:{},",
+                gen_props(helpers, el.attrs.iter().map(prop_entry))
+            );
+        }
+        if !el.props.is_empty() || !directive_props.is_empty() {
+            let props = el.props.iter().map(prop_entry).chain(
+                directive_props
+                    .iter()
+                    .map(|(name, value)| (*name, value.clone(), false)),
+            );
+            data += &format!("domProps:{},", gen_props(helpers, props));
+        }
+        if let Some(events) = &el.events {
+            data += &format!("{},", gen_handlers(helpers, events, false));
+        }
+        if let Some(native_events) = &el.native_events {
+            data += &format!("{},", gen_handlers(helpers, native_events, true));
+        }
+        // slot target, only for non-scoped slots
+        if let (Some(slot_target), None) = (&el.slot_target, &el.slot_scope) {
+            data += &format!("slot:{},", slot_target);
+        }
+        if let Some(scoped_slots) = &el.scoped_slots {
+            data += &format!("{},", self.gen_scoped_slots(node, scoped_slots));
+        }
+        if el.inline_template {
+            if let Some(inline_template) = self.gen_inline_template(node) {
+                data += &format!("{},", inline_template);
+            }
+        }
+
+        if data.is_empty()
+            && el.dynamic_attrs.is_empty()
+            && el.v_bind_object.is_none()
+            && el.v_on_object.is_none()
+        {
+            return None;
+        }
+
+        let mut data = format!("{{{}}}", data.trim_end_matches(','));
+
+        // v-bind with dynamic arguments
+        if !el.dynamic_attrs.is_empty() {
+            data = format!(
+                "{}({},\"{}\",{})",
+                helpers.bind_object_props,
+                data,
+                el.token.data,
+                gen_props(helpers, el.dynamic_attrs.iter().map(prop_entry))
+            );
+        }
+        // v-bind="object"
+        if let Some(v_bind_object) = &el.v_bind_object {
+            data = format!(
+                "{}({},'{}',{},{}{})",
+                helpers.bind_object_props,
+                data,
+                el.token.data,
+                v_bind_object.value,
+                v_bind_object.prop,
+                if v_bind_object.sync { ",true" } else { "" }
+            );
+        }
+        // v-on="object"
+        if let Some(v_on_object) = &el.v_on_object {
+            data = format!(
+                "{}({},{})",
+                helpers.bind_object_listeners, data, v_on_object
+            );
+        }
+
+        Some(data)
+    }
+
+    // v-once, renders the element once and keeps the result. Inside v-for `_o(render,once_id,key)`
+    // caches every iteration, otherwise the element is hoisted like a static root
+    fn gen_once(&self, node: &ASTNode, processed: Processed) -> String {
+        // the condition is evaluated on every render, only the rendered branch is cached
+        if node.el.if_val.is_some() && !processed.if_processed {
+            return self.gen_if(
+                node,
+                processed,
+                &|node, processed| self.gen_element_processed(node, processed),
+                &format!("{}()", self.options.helpers.create_empty),
+            );
+        }
+
+        let processed = Processed {
+            once_processed: true,
+            ..processed
+        };
+
+        // inside v-for every iteration needs its own cache entry, keyed by the v-for key
+        let mut in_for = false;
+        let mut for_key = None;
+        let mut parent = node.parent.as_ref().and_then(|parent| parent.upgrade());
+        while let Some(parent_ptr) = parent {
+            let parent_node = parent_ptr.borrow();
+            if parent_node.el.for_value.is_some() {
+                in_for = true;
+                for_key = parent_node.el.key.clone();
+                break;
+            }
+            parent = parent_node
+                .parent
+                .as_ref()
+                .and_then(|parent| parent.upgrade());
+        }
+
+        if in_for && for_key.is_none() {
+            if self.options.dev {
+                self.warn
+                    .borrow_mut()
+                    .call("v-once can only be used inside v-for that is keyed.");
+            }
+            return self.gen_element_processed(node, processed);
+        }
+
+        let Some(key) = for_key else {
+            return self.gen_static(node, processed);
+        };
+
+        let code = self.gen_element_processed(node, processed);
+        let once_id = self.once_id.get();
+        self.once_id.set(once_id + 1);
+
+        format!(
+            "{}({},{},{})",
+            self.options.helpers.mark_once, code, once_id, key
+        )
+    }
+
+    // `inlineTemplate:{render:...,staticRenderFns:[...]}`, the only child element is compiled
+    // as a separate template with its own static render functions and v-once ids
+    fn gen_inline_template(&self, node: &ASTNode) -> Option<String> {
+        let ast = node
+            .children
+            .first()
+            .map(|child| child.borrow())
+            .filter(|child| child.el.kind == ASTElementKind::Element);
+
+        if self.options.dev && (node.children.len() != 1 || ast.is_none()) {
+            self.warn.borrow_mut().call_with_range(
+                "Inline-template components must have exactly one child element.",
+                node.el.range,
+            );
+        }
+
+        let ast = ast?;
+        let outer_static_render_fns = self.static_render_fns.take();
+        let outer_once_id = self.once_id.replace(0);
+
+        let render = if ast.el.token.data.eq_ignore_ascii_case("script") {
+            "null".to_string()
+        } else {
+            self.gen_element(&ast)
+        };
+        let static_render_fns: Vec<String> = self
+            .static_render_fns
+            .replace(outer_static_render_fns)
+            .into_iter()
+            .map(|code| format!("function(){{{}}}", code))
+            .collect();
+        self.once_id.set(outer_once_id);
+
+        Some(format!(
+            "inlineTemplate:{{render:function(){{with(this){{return {}}}}},staticRenderFns:[{}]}}",
+            render,
+            static_render_fns.join(",")
+        ))
+    }
+
+    // hoists the element into its own render function, `_m(index[,true])`
+    fn gen_static(&self, node: &ASTNode, processed: Processed) -> String {
+        let processed = Processed {
+            static_processed: true,
+            ..processed
+        };
+        let code = format!(
+            "with(this){{return {}}}",
+            self.gen_element_processed(node, processed)
+        );
+
+        let mut static_render_fns = self.static_render_fns.borrow_mut();
+        static_render_fns.push(code);
+
+        format!(
+            "{}({}{})",
+            self.options.helpers.render_static,
+            static_render_fns.len() - 1,
+            if node.el.static_in_for { ",true" } else { "" }
+        )
+    }
+
+    // `(a)?render_a:(b)?render_b:render_c`, the first condition block is the element itself
+    fn gen_if(
+        &self,
+        node: &ASTNode,
+        processed: Processed,
+        gen_block: &dyn Fn(&ASTNode, Processed) -> String,
+        alt_empty: &str,
+    ) -> String {
+        let processed = Processed {
+            if_processed: true,
+            ..processed
+        };
+        let conditions = node.el.if_conditions.as_deref().unwrap_or_default();
+
+        self.gen_if_conditions(node, conditions, processed, gen_block, alt_empty)
+    }
+
+    fn gen_if_conditions(
+        &self,
+        node: &ASTNode,
+        conditions: &[IfCondition],
+        processed: Processed,
+        gen_block: &dyn Fn(&ASTNode, Processed) -> String,
+        alt_empty: &str,
+    ) -> String {
+        let Some((condition, rest)) = conditions.split_first() else {
+            return alt_empty.to_string();
+        };
+
+        let block = if condition.block_id == node.id {
+            gen_block(node, processed)
+        } else {
+            match self.tree.get(condition.block_id) {
+                Some(block) => gen_block(&block.borrow(), Processed::default()),
+                None => alt_empty.to_string(),
+            }
+        };
+
+        match &condition.exp {
+            Some(exp) => format!(
+                "({})?{}:{}",
+                exp,
+                block,
+                self.gen_if_conditions(node, rest, processed, gen_block, alt_empty)
+            ),
+            None => block,
+        }
+    }
+
+    // `_l((list),function(alias,iterator1,iterator2){return ...})`
+    fn gen_for(
+        &self,
+        node: &ASTNode,
+        processed: Processed,
+        gen_block: &dyn Fn(&ASTNode, Processed) -> String,
+    ) -> String {
+        let el = &node.el;
+        let params: Vec<&str> = [&el.alias, &el.iterator1, &el.iterator2]
+            .into_iter()
+            .flatten()
+            .map(|param| param.as_str())
+            .collect();
+        let processed = Processed {
+            for_processed: true,
+            ..processed
+        };
+
+        format!(
+            "{}(({}),function({}){{return {}}})",
+            self.options.helpers.render_list,
+            el.for_value.as_deref().unwrap_or_default(),
+            params.join(","),
+            gen_block(node, processed)
+        )
+    }
+
+    fn gen_scoped_slots(
+        &self,
+        node: &ASTNode,
+        slots: &UniCaseBTreeMap<Rc<RefCell<ASTNode>>>,
+    ) -> String {
+        // by default scoped slots are considered "stable", this allows child
+        // components with only scoped slots to skip forced updates from parent.
+        // but in some cases we have to bail-out of this optimization
+        // for example if the slot contains dynamic names, has v-if or v-for on them...
+        let mut needs_force_update = node.el.for_value.is_some()
+            || slots.values().any(|slot| {
+                let slot = slot.borrow();
+                slot.el.slot_target_dynamic
+                    || slot.el.if_val.is_some()
+                    || slot.el.for_value.is_some()
+                    || contains_slot_child(&slot)
+            });
+
+        // #9534: if a component with scoped slots is inside a conditional branch,
+        // it's possible for the same component to be reused but with different
+        // compiled slot content. To avoid that, we generate a unique key based on
+        // the generated code of all the slot contents.
+        let mut needs_key = node.el.if_val.is_some();
+
+        // OR when it is inside another scoped slot or v-for (the reactivity may be
+        // disconnected due to the intermediate scope variable)
+        if !needs_force_update {
+            let mut parent = node.parent.as_ref().and_then(|parent| parent.upgrade());
+            while let Some(parent_ptr) = parent {
+                let parent_node = parent_ptr.borrow();
+                let has_slot_scope = parent_node
+                    .el
+                    .slot_scope
+                    .as_ref()
+                    .is_some_and(|scope| scope != EMPTY_SLOT_SCOPE_TOKEN);
+
+                if has_slot_scope || parent_node.el.for_value.is_some() {
+                    needs_force_update = true;
+                    break;
+                }
+                if parent_node.el.if_val.is_some() {
+                    needs_key = true;
+                }
+                parent = parent_node
+                    .parent
+                    .as_ref()
+                    .and_then(|parent| parent.upgrade());
+            }
+        }
+
+        let generated_slots: Vec<String> = slots
+            .values()
+            .map(|slot| self.gen_scoped_slot(&slot.borrow(), Processed::default()))
+            .collect();
+        let generated_slots = generated_slots.join(",");
+
+        format!(
+            "scopedSlots:{}([{}]{})",
+            self.options.helpers.resolve_scoped_slots,
+            generated_slots,
+            if needs_force_update {
+                ",null,true".to_string()
+            } else if needs_key {
+                format!(",null,false,{}", hash(&generated_slots))
+            } else {
+                String::new()
+            }
+        )
+    }
+
+    fn gen_scoped_slot(&self, node: &ASTNode, processed: Processed) -> String {
+        let el = &node.el;
+        let is_legacy_syntax = node.has_raw_attr("slot-scope");
+
+        if el.if_val.is_some() && !processed.if_processed && !is_legacy_syntax {
+            return self.gen_if(
+                node,
+                processed,
+                &|node, processed| self.gen_scoped_slot(node, processed),
+                "null",
+            );
+        }
+        if el.for_value.is_some() && !processed.for_processed {
+            return self.gen_for(node, processed, &|node, processed| {
+                self.gen_scoped_slot(node, processed)
+            });
+        }
+
+        let slot_scope = match el.slot_scope.as_deref() {
+            Some(EMPTY_SLOT_SCOPE_TOKEN) | None => "",
+            Some(slot_scope) => slot_scope,
+        };
+        let body = if &*el.token.data == "template" {
+            let children = self
+                .gen_children(node, false)
+                .unwrap_or_else(|| "undefined".to_string());
+
+            match (&el.if_val, is_legacy_syntax) {
+                (Some(if_val), true) => format!("({})?{}:undefined", if_val, children),
+                _ => children,
+            }
+        } else {
+            self.gen_element_processed(node, processed)
+        };
+
+        // reverse proxy v-slot without scope on this.$slots
+        format!(
+            "{{key:{},fn:function({}){{return {}}}{}}}",
+            el.slot_target.as_deref().unwrap_or("\"default\""),
+            slot_scope,
+            body,
+            if slot_scope.is_empty() {
+                ",proxy:true"
+            } else {
+                ""
+            }
+        )
+    }
+
+    // `_t(name,[fallback],attrs,bind)`
+    fn gen_slot(&self, node: &ASTNode) -> String {
+        let el = &node.el;
+        let slot_name = el.slot_name.as_deref().unwrap_or("\"default\"");
+        let children = self.gen_children(node, false);
+        let mut res = format!("{}({}", self.options.helpers.render_slot, slot_name);
+        if let Some(children) = &children {
+            res += &format!(",{}", children);
+        }
+
+        let attrs = if el.attrs.is_empty() && el.dynamic_attrs.is_empty() {
+            None
+        } else {
+            Some(gen_props(
+                &self.options.helpers,
+                el.attrs.iter().chain(el.dynamic_attrs.iter()).map(|attr| {
+                    let (name, value, dynamic) = prop_entry(attr);
+                    (to_camel(name), value, dynamic)
+                }),
+            ))
+        };
+        let bind = el.v_bind_object.as_ref().map(|bind| &bind.value);
+
+        if (attrs.is_some() || bind.is_some()) && children.is_none() {
+            res += ",null";
+        }
+        if let Some(attrs) = &attrs {
+            res += &format!(",{}", attrs);
+        }
+        if let Some(bind) = bind {
+            res += &format!("{},{}", if attrs.is_some() { "" } else { ",null" }, bind);
+        }
+
+        res + ")"
+    }
+
+    // `[child,...],normalization_type`, the normalization type is only emitted for the
+    // children argument of `_c` (check_skip), 0 is left out
+    pub fn gen_children(&self, node: &ASTNode, check_skip: bool) -> Option<String> {
+        let first = node.children.first()?.borrow();
+
+        // a single v-for child is passed as the children array itself
+        if node.children.len() == 1
+            && first.el.for_value.is_some()
+            && !first.el.token.data.eq_ignore_ascii_case("template")
+            && !first.el.token.data.eq_ignore_ascii_case("slot")
+        {
+            let normalization_type = match (check_skip, first.is_maybe_component()) {
+                (false, _) => "",
+                (true, true) => ",1",
+                (true, false) => ",0",
+            };
+            return Some(format!(
+                "{}{}",
+                self.gen_element(&first),
+                normalization_type
+            ));
+        }
+
+        let children: Vec<String> = node
+            .children
+            .iter()
+            .map(|child| self.gen_node(&child.borrow()))
+            .collect();
+        let normalization_type = match node.el.normalization_type {
+            NormalizationType::None => String::new(),
+            _ if !check_skip => String::new(),
+            normalization_type => format!(",{}", normalization_type as u8),
+        };
+
+        Some(format!("[{}]{}", children.join(","), normalization_type))
+    }
+
+    pub fn gen_node(&self, node: &ASTNode) -> String {
+        match node.el.kind {
+            ASTElementKind::Element | ASTElementKind::Root => self.gen_element(node),
+            ASTElementKind::Text if node.el.is_comment => gen_comment(node, &self.options.helpers),
+            ASTElementKind::Text | ASTElementKind::Expression => {
+                gen_text(node, &self.options.helpers)
+            }
+        }
+    }
+}
+
+fn contains_slot_child(node: &ASTNode) -> bool {
+    if node.el.kind != ASTElementKind::Element {
+        return false;
+    }
+    if &*node.el.token.data == "slot" {
+        return true;
+    }
+
+    node.children
+        .iter()
+        .any(|child| contains_slot_child(&child.borrow()))
+}
+
+// string hash of the generated slot code, same algorithm as vue so keys stay comparable
+fn hash(text: &str) -> u32 {
+    let mut hash: i32 = 5381;
+    for code in text.encode_utf16().collect::<Vec<u16>>().into_iter().rev() {
+        hash = hash.wrapping_mul(33) ^ code as i32;
+    }
+
+    hash as u32
+}
+
+fn gen_directives<'a>(
+    node: &'a ASTNode,
+    helpers: &RenderHelpers,
+    directive_props: &mut Vec<(&'a str, String)>,
+) -> Option<String> {
+    let directives = node.el.directives.as_ref()?;
+    let mut res = vec![];
+
+    for directive in directives {
+        let value = directive.value.as_deref().unwrap_or_default();
+
+        // directives that are fully handled at compile time do not need a runtime counterpart
+        match directive.name.as_str() {
+            "cloak" => continue,
+            "text" => {
+                directive_props.push(("textContent", format!("{}({})", helpers.to_string, value)));
+                continue;
+            }
+            "html" => {
+                directive_props.push(("innerHTML", format!("{}({})", helpers.to_string, value)));
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut code = format!(
+            "{{name:\"{}\",rawName:\"{}\"",
+            directive.name, directive.raw_name
+        );
+        // an empty value is falsy in vue, neither the value nor the expression is generated
+        if let Some(value) = directive.value.as_deref().filter(|v| !v.trim().is_empty()) {
+            code += &format!(",value:({}),expression:{}", value, to_js_string(value));
+        }
+        if let Some(arg) = &directive.arg {
+            if directive.is_dynamic_arg {
+                code += &format!(",arg:{}", arg);
+            } else {
+                code += &format!(",arg:\"{}\"", arg);
+            }
+        }
+        if !directive.modifiers.is_empty() {
+            let modifiers: Vec<String> = directive
+                .modifiers
+                .iter()
+                .map(|modifier| format!("{}:true", to_js_string(modifier)))
+                .collect();
+            code += &format!(",modifiers:{{{}}}", modifiers.join(","));
+        }
+        code += "}";
+
+        res.push(code);
+    }
+
+    if res.is_empty() {
+        return None;
+    }
+
+    Some(format!("directives:[{}]", res.join(",")))
+}
+
+// (name, value code, dynamic name)
+fn prop_entry(item: &AttrItem) -> (&str, String, bool) {
+    let value = match (&item.value, item.literal) {
+        (value, true) => to_js_string(value.as_deref().unwrap_or_default()),
+        (Some(value), false) => transform_special_newlines(value),
+        (None, false) => "\"\"".to_string(),
+    };
+
+    (&item.name, value, item.dynamic)
+}
+
+// `{"name":value}`, dynamic names are merged in at runtime with `_d({...},[name,value])`
+fn gen_props<N: AsRef<str>>(
+    helpers: &RenderHelpers,
+    props: impl Iterator<Item = (N, String, bool)>,
+) -> String {
+    let mut static_props = vec![];
+    let mut dynamic_props = vec![];
+
+    for (name, value, dynamic) in props {
+        if dynamic {
+            dynamic_props.push(format!("{},{}", name.as_ref(), value));
+        } else {
+            static_props.push(format!("{}:{}", to_js_string(name.as_ref()), value));
+        }
+    }
+
+    let static_props = format!("{{{}}}", static_props.join(","));
+    if dynamic_props.is_empty() {
+        static_props
+    } else {
+        format!(
+            "{}({},[{}])",
+            helpers.bind_dynamic_keys,
+            static_props,
+            dynamic_props.join(",")
+        )
+    }
+}
+
+fn gen_handlers(
+    helpers: &RenderHelpers,
+    events: &BTreeMap<String, Vec<Handler>>,
+    is_native: bool,
+) -> String {
+    let prefix = if is_native { "nativeOn:" } else { "on:" };
+    let mut static_handlers = vec![];
+    let mut dynamic_handlers = vec![];
+
+    for (name, handlers) in events {
+        let code = match handlers.as_slice() {
+            [handler] => gen_handler(helpers, handler),
+            handlers => format!(
+                "[{}]",
+                handlers
+                    .iter()
+                    .map(|handler| gen_handler(helpers, handler))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+        };
+
+        if handlers.first().is_some_and(|handler| handler.dynamic) {
+            dynamic_handlers.push(format!("{},{}", name, code));
+        } else {
+            static_handlers.push(format!("\"{}\":{}", name, code));
+        }
+    }
+
+    let static_handlers = format!("{{{}}}", static_handlers.join(","));
+    if dynamic_handlers.is_empty() {
+        format!("{}{}", prefix, static_handlers)
+    } else {
+        format!(
+            "{}{}({},[{}])",
+            prefix,
+            helpers.bind_dynamic_keys,
+            static_handlers,
+            dynamic_handlers.join(",")
+        )
+    }
+}
+
+// method paths and function expressions are used as the handler, inline statements are wrapped
+// in a function. Modifiers add key filters and guards in front of the handler code
+fn gen_handler(helpers: &RenderHelpers, handler: &Handler) -> String {
+    let value = &handler.value;
+    let is_method_path = SIMPLE_PATH_RE.is_match(value);
+    let is_function_expression = FN_EXP_RE.is_match(value);
+    let is_function_invocation = SIMPLE_PATH_RE.is_match(&FN_INVOKE_RE.replace(value, ""));
+
+    if handler.modifiers.is_empty() {
+        if is_method_path || is_function_expression {
+            return value.to_string();
+        }
+
+        return if is_function_invocation {
+            format!("function($event){{return {}}}", value)
+        } else {
+            format!("function($event){{{}}}", value)
+        };
+    }
+
+    let mut gen_modifier_code = String::new();
+    let mut keys = vec![];
+    for modifier in &handler.modifiers {
+        let modifier = modifier.as_str();
+        if let Some(code) = modifier_code(modifier) {
+            gen_modifier_code += &code;
+            // left and right are also arrow keys
+            if key_code(modifier).is_some() {
+                keys.push(modifier);
+            }
+        } else if modifier == "exact" {
+            let conditions: Vec<String> = ["ctrl", "shift", "alt", "meta"]
+                .into_iter()
+                .filter(|key_modifier| !handler.modifiers.iter().any(|m| m == key_modifier))
+                .map(|key_modifier| format!("$event.{}Key", key_modifier))
+                .collect();
+            gen_modifier_code += &format!("if({})return null;", conditions.join("||"));
+        } else {
+            keys.push(modifier);
+        }
+    }
+
+    let mut code = String::new();
+    if !keys.is_empty() {
+        code += &gen_key_filter(helpers, &keys);
+    }
+    // modifiers like prevent and stop run after the key filtering
+    code += &gen_modifier_code;
+
+    let handler_code = if is_method_path {
+        format!("return {}.apply(null, arguments)", value)
+    } else if is_function_expression {
+        format!("return ({}).apply(null, arguments)", value)
+    } else if is_function_invocation {
+        format!("return {}", value)
+    } else {
+        value.to_string()
+    };
+
+    format!("function($event){{{}{}}}", code, handler_code)
+}
+
+// key filters only apply to keyboard events
+fn gen_key_filter(helpers: &RenderHelpers, keys: &[&str]) -> String {
+    let filters: Vec<String> = keys
+        .iter()
+        .map(|key| match key.parse::<u32>() {
+            Ok(key_code) if key_code > 0 => format!("$event.keyCode!=={}", key_code),
+            _ => format!(
+                "{}($event.keyCode,{},{},$event.key,{})",
+                helpers.check_key_codes,
+                to_js_string(key),
+                key_code(key).unwrap_or("undefined"),
+                key_name(key).unwrap_or("undefined")
+            ),
+        })
+        .collect();
+
+    format!(
+        "if(!$event.type.indexOf('key')&&{})return null;",
+        filters.join("&&")
+    )
+}
+
+pub fn gen_text(node: &ASTNode, helpers: &RenderHelpers) -> String {
+    match (&node.el.kind, &node.el.expression) {
+        (ASTElementKind::Expression, Some(expression)) => {
+            format!("{}({})", helpers.create_text, expression)
+        }
+        _ => format!(
+            "{}({})",
+            helpers.create_text,
+            to_js_string(&node.el.token.data)
+        ),
+    }
+}
+
+pub fn gen_comment(node: &ASTNode, helpers: &RenderHelpers) -> String {
+    format!(
+        "{}({})",
+        helpers.create_empty,
+        to_js_string(&node.el.token.data)
+    )
+}
+
+// line and paragraph separators are valid in javascript expressions but not inside string literals
+fn transform_special_newlines(text: &str) -> String {
+    text.replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+
+// JSON.stringify plus escaping the line terminators JSON allows but javascript string literals do not
+pub fn to_js_string(text: &str) -> String {
+    transform_special_newlines(&serde_json::to_string(text).unwrap())
+}
@@ -111,3 +111,82 @@ pub fn gen_assignment_code(val: &(Box<str>, QuoteType), assignment: &str) -> Str
         Some(key) => format!("$set({}, {}, {})", res.exp, key, assignment),
     }
 }
+
+fn is_identifier_start(chr: char) -> bool {
+    chr.is_alphabetic() || chr == '_' || chr == '$'
+}
+
+fn is_identifier_part(chr: char) -> bool {
+    chr.is_alphanumeric() || chr == '_' || chr == '$'
+}
+
+// true for expressions that can be assigned to, an identifier followed by
+// member accesses: `a`, `a.b.c`, `a[b]`, `a['b'].c`. Calls such as `a.b()` are not
+pub fn is_assignable(val: &str) -> bool {
+    let chars: Vec<char> = val.trim().chars().collect();
+    let mut index = 0;
+
+    let parse_identifier = |index: &mut usize| {
+        if !chars
+            .get(*index)
+            .is_some_and(|chr| is_identifier_start(*chr))
+        {
+            return false;
+        }
+        while chars
+            .get(*index)
+            .is_some_and(|chr| is_identifier_part(*chr))
+        {
+            *index += 1;
+        }
+        true
+    };
+
+    if !parse_identifier(&mut index) {
+        return false;
+    }
+
+    while index < chars.len() {
+        match chars[index] {
+            '.' => {
+                index += 1;
+                if !parse_identifier(&mut index) {
+                    return false;
+                }
+            }
+            '[' => {
+                let start = index;
+                let mut in_bracket = 0;
+                let mut string_quote: Option<char> = None;
+                while index < chars.len() {
+                    let chr = chars[index];
+                    index += 1;
+                    match string_quote {
+                        Some(quote) if chr == quote => string_quote = None,
+                        Some(_) => {}
+                        None if chr == '"' || chr == '\'' || chr == '`' => string_quote = Some(chr),
+                        None if chr == '[' => in_bracket += 1,
+                        None if chr == ']' => {
+                            in_bracket -= 1;
+                            if in_bracket == 0 {
+                                break;
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                // unbalanced or empty brackets
+                if in_bracket != 0
+                    || chars[start + 1..index - 1]
+                        .iter()
+                        .all(|chr| chr.is_whitespace())
+                {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+
+    true
+}
@@ -1,76 +1,72 @@
-use rs_html_parser_tokenizer_tokens::QuoteType;
-
-pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
-    let mut in_single = quote_type == &QuoteType::Single;
-    let mut in_double = quote_type == &QuoteType::Double;
+// Returns the expression with its filters applied and the names of those filters in order.
+// The expression starts outside of any string, whatever quotes the attribute value was written in
+pub fn parse_filters(expr: &str) -> (String, Vec<String>) {
+    let bytes = expr.as_bytes();
+    let mut in_single = false;
+    let mut in_double = false;
     let mut in_template_string = false;
     let mut in_regex = false;
     let mut curly = 0;
     let mut square = 0;
     let mut paren = 0;
     let mut last_filter_index = 0;
-    let mut c: char = '0';
+    let mut c: u8 = 0;
     let mut prev;
     let mut expression: Option<String> = None;
     let mut filters: Vec<String> = Vec::new();
 
-    for (i, char) in expr.chars().enumerate() {
+    // all the characters of interest are ascii, so byte indexes are always on char boundaries
+    for (i, &byte) in bytes.iter().enumerate() {
         prev = c;
-        c = char;
+        c = byte;
 
         if in_single {
-            if c == '\'' && prev != '\\' {
+            if c == b'\'' && prev != b'\\' {
                 in_single = false;
             }
         } else if in_double {
-            if c == '\"' && prev != '\\' {
+            if c == b'"' && prev != b'\\' {
                 in_double = false;
             }
         } else if in_template_string {
-            if c == '`' && prev != '\\' {
+            if c == b'`' && prev != b'\\' {
                 in_template_string = false;
             }
         } else if in_regex {
-            if c == '/' && prev != '\\' {
+            if c == b'/' && prev != b'\\' {
                 in_regex = false;
             }
-        } else if c == '|'
-            && expr.chars().nth(i + 1).unwrap() != '|'
-            && expr.chars().nth(i - 1).unwrap() != '|'
+        } else if c == b'|' // pipe
+            && bytes.get(i + 1) != Some(&b'|')
+            && prev != b'|'
             && curly == 0
             && square == 0
             && paren == 0
         {
             if expression.is_none() {
+                // first filter, end of expression
                 last_filter_index = i + 1;
                 expression = Some(expr[..i].trim().to_string());
             } else {
-                push_filter(&mut filters, &expr, &mut last_filter_index, i);
+                push_filter(&mut filters, expr, &mut last_filter_index, i);
             }
         } else {
             match c {
-                '\"' => in_double = true,
-                '\'' => in_single = true,
-                '`' => in_template_string = true,
-                '(' => paren += 1,
-                ')' => paren -= 1,
-                '[' => square += 1,
-                ']' => square -= 1,
-                '{' => curly += 1,
-                '}' => curly -= 1,
+                b'"' => in_double = true,
+                b'\'' => in_single = true,
+                b'`' => in_template_string = true,
+                b'(' => paren += 1,
+                b')' => paren -= 1,
+                b'[' => square += 1,
+                b']' => square -= 1,
+                b'{' => curly += 1,
+                b'}' => curly -= 1,
                 _ => (),
             }
-            if c == '/' {
-                let mut j: i32 = i as i32 - 1;
-                let mut p: char = '0';
-                while j >= 0 {
-                    p = expr.chars().nth(j as usize).unwrap();
-                    if p != ' ' {
-                        break;
-                    }
-                    j -= 1;
-                }
-                if j < 0 || !valid_division_char(p) {
+            if c == b'/' {
+                // a slash starts a regex unless it follows something that can be divided
+                let p = bytes[..i].iter().rev().find(|p| **p != b' ');
+                if !p.is_some_and(|p| valid_division_char(*p as char)) {
                     in_regex = true;
                 }
             }
@@ -80,16 +76,24 @@ pub fn parse_filters((expr, quote_type): &(Box<str>, QuoteType)) -> String {
     if expression.is_none() {
         expression = Some(expr[..].trim().to_string());
     } else if last_filter_index != 0 {
-        push_filter(&mut filters, &expr, &mut last_filter_index, expr.len());
+        push_filter(&mut filters, expr, &mut last_filter_index, expr.len());
     }
 
-    if !filters.is_empty() {
-        for filter in filters {
-            expression = Some(wrap_filter(expression.unwrap(), filter));
-        }
+    let mut expression = expression.unwrap();
+    let mut names = Vec::with_capacity(filters.len());
+    for filter in filters {
+        names.push(filter_name(&filter).to_string());
+        expression = wrap_filter(expression, filter);
     }
 
-    expression.unwrap()
+    (expression, names)
+}
+
+fn filter_name(filter: &str) -> &str {
+    match filter.find('(') {
+        None => filter,
+        Some(i) => filter[..i].trim(),
+    }
 }
 
 fn push_filter(filters: &mut Vec<String>, exp: &str, last_filter_index: &mut usize, i: usize) {
@@ -108,21 +112,24 @@ fn valid_division_char(p: char) -> bool {
         || p == ']'
 }
 
+// `exp | name(args)` becomes `_f("name")(exp,args)`, filters without arguments get the expression only
 fn wrap_filter(exp: String, filter: String) -> String {
-    let i = filter.find('(');
-    if i.is_none() {
-        return format!("_f(\"{}\")({})", filter, exp);
-    } else {
-        let (name, args) = filter.split_at(i.unwrap());
-        return format!(
-            "_f(\"{}\")({}{})",
-            name,
-            exp,
-            if args != ")" {
-                ",".to_owned() + args
-            } else {
-                args.to_string()
-            }
-        );
+    match filter.find('(') {
+        None => format!("_f(\"{}\")({})", filter, exp),
+        Some(i) => {
+            let name = &filter[..i];
+            let args = &filter[i + 1..];
+
+            format!(
+                "_f(\"{}\")({}{}",
+                name,
+                exp,
+                if args != ")" {
+                    format!(",{}", args)
+                } else {
+                    args.to_string()
+                }
+            )
+        }
     }
 }
@@ -1,16 +1,17 @@
+/**
+ * Camelize a hyphen-delimited string, the other characters keep their case.
+ */
 pub fn to_camel(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut capitalize_next = false;
     for c in s.chars() {
         if c == ' ' || c == '_' || c == '-' {
             capitalize_next = true;
+        } else if capitalize_next {
+            result.push(c.to_ascii_uppercase());
+            capitalize_next = false;
         } else {
-            if capitalize_next {
-                result.push(c.to_ascii_uppercase());
-                capitalize_next = false;
-            } else {
-                result.push(c.to_ascii_lowercase());
-            }
+            result.push(c);
         }
     }
     result
@@ -1,8 +1,14 @@
 pub mod ast_tree;
+pub mod codegen;
 mod directives_model;
 mod filter_parser;
 mod helpers;
+pub mod optimizer;
+#[cfg(feature = "serde")]
+mod serialize;
+mod source_scan;
 mod text_parser;
+pub mod trace;
 mod uni_codes;
 mod util;
 mod warn_logger;
@@ -11,30 +17,42 @@ pub mod web;
 extern crate lazy_static;
 
 use crate::ast_tree::{
-    create_ast_element, ASTElement, ASTElementKind, ASTNode, ASTTree, IfCondition,
+    create_ast_element, ASTElement, ASTElementKind, ASTNode, ASTTree, SourceRange,
 };
+use crate::source_scan::{scan_source, CloseTagSource, LineIndex, TextSource};
 use crate::text_parser::parse_text;
+use crate::trace::phase_span;
 use crate::uni_codes::{UC_TYPE, UC_V_FOR};
-use crate::util::{get_attribute_value, has_attribute};
-use crate::warn_logger::WarnLogger;
-use crate::web::element::get_namespace;
+use crate::util::{
+    decode_custom_entities, get_attribute_value, get_bound_attribute_value, has_attribute,
+};
+use crate::warn_logger::collecting_warn_logger;
+pub use crate::warn_logger::{RangeWarnLogger, WarnLogger};
+use crate::web::compiler::class::ClassModule;
+use crate::web::compiler::model::ModelModule;
+use crate::web::compiler::style::StyleModule;
+use crate::web::element::{get_tag_namespace, is_block_tag, is_unary_tag};
 use lazy_static::lazy_static;
 use regex::Regex;
 use rs_html_parser::{Parser, ParserOptions};
 use rs_html_parser_tokenizer::TokenizerOptions;
 use rs_html_parser_tokens::{Token, TokenKind};
-use std::cell::{RefCell, RefMut};
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::format;
 use std::rc::Rc;
 use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
 
 lazy_static! {
-    static ref INVALID_ATTRIBUTE_RE: Regex = Regex::new(r##"/[\s"'<>\/=]/"##).unwrap();
+    static ref INVALID_ATTRIBUTE_RE: Regex = Regex::new(r##"[\s"'<>\/=]"##).unwrap();
     static ref FOR_ALIAS_RE: Regex = Regex::new(r"([\s\S]*?)\s+(?:in|of)\s+([\s\S]*)").unwrap();
     static ref FOR_ITERATOR_RE: Regex = Regex::new(r",([^,\}\]]*)(?:,([^,\}\]]*))?$").unwrap();
     static ref STRIP_PARENS_RE: Regex = Regex::new(r"^\(|\)$").unwrap();
     static ref DYNAMIC_ARG_RE: Regex = Regex::new(r"^\[.*\]$").unwrap();
+    // whitespace ends the attribute name, `:[a b]` leaves `:[a` with an unclosed argument
+    static ref UNCLOSED_DYNAMIC_ARG_RE: Regex = Regex::new(r"^(?:v-[\w-]+:|[:@#.])\[[^\]]*$").unwrap();
     static ref ARG_RE: Regex = Regex::new(r":(.*)$").unwrap();
     static ref BIND_RE: Regex = Regex::new(r"^:|^\.|^v-bind:").unwrap();
     static ref PROP_BIND_RE: Regex = Regex::new(r"^\.").unwrap();
@@ -45,11 +63,9 @@ lazy_static! {
     static ref DIR_RE: Regex = Regex::new(r"^(v-|@|:|#)").unwrap();
     static ref DIR_RE_VBIND_SHORT_HAND: Regex = Regex::new(r"^v-|^@|^:|^\.|^#").unwrap();
     static ref ON_RE: Regex = Regex::new(r"^@|^v-on:").unwrap();
-}
-
-// TODO: Move to options
-fn print_line(message: &str) {
-    println!("{}", message)
+    static ref MISPLACED_DIRECTIVE_RE: Regex = Regex::new(r"^(?::|@|\.|v-bind:|v-on:)(v-.+)$").unwrap();
+    static ref EMPTY_DIRECTIVE_NAME_RE: Regex = Regex::new(r"^v-:(.+)$").unwrap();
+    static ref NAMED_ENTITY_RE: Regex = Regex::new(r"&([A-Za-z][A-Za-z0-9]*);").unwrap();
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
@@ -60,7 +76,48 @@ pub enum WhitespaceHandling {
     Ignore,
 }
 
-#[derive(Default)]
+// names of the runtime helpers called by the generated render code, the defaults are the
+// instance methods of Vue 2. Filters (`_f`) and dynamic event modifiers (`_p`) are always
+// generated with the Vue 2 names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderHelpers {
+    pub create_element: String,
+    pub create_text: String,
+    pub create_empty: String,
+    // converts interpolated values to their display string
+    pub to_string: String,
+    pub render_list: String,
+    pub render_slot: String,
+    pub render_static: String,
+    pub mark_once: String,
+    pub resolve_scoped_slots: String,
+    pub bind_object_props: String,
+    pub bind_dynamic_keys: String,
+    pub bind_object_listeners: String,
+    // key modifiers of keyboard event handlers
+    pub check_key_codes: String,
+}
+
+impl Default for RenderHelpers {
+    fn default() -> Self {
+        RenderHelpers {
+            create_element: "_c".to_string(),
+            create_text: "_v".to_string(),
+            create_empty: "_e".to_string(),
+            to_string: "_s".to_string(),
+            render_list: "_l".to_string(),
+            render_slot: "_t".to_string(),
+            render_static: "_m".to_string(),
+            mark_once: "_o".to_string(),
+            resolve_scoped_slots: "_u".to_string(),
+            bind_object_props: "_b".to_string(),
+            bind_dynamic_keys: "_d".to_string(),
+            bind_object_listeners: "_g".to_string(),
+            check_key_codes: "_k".to_string(),
+        }
+    }
+}
+
 pub struct CompilerOptions {
     pub dev: bool,
     pub is_ssr: bool,
@@ -68,14 +125,302 @@ pub struct CompilerOptions {
     pub v_bind_prop_short_hand: bool,
     pub preserve_comments: bool,
     pub whitespace_handling: WhitespaceHandling,
+    // condense mode only, also drops the whitespace-only text next to block-level elements,
+    // e.g. between two <p>. Whitespace between inline content is still condensed to a space
+    pub trim_block_whitespace: bool,
+    // v-slot and the # shorthand of Vue 2.6, on by default. When off v-slot is kept as a regular directive
     pub new_slot_syntax: bool,
+    // opt-in, keys v-for elements that have no key with the iteration index.
+    // Only meant to silence the missing key warnings while migrating templates.
+    pub auto_key_v_for: bool,
+    // compat, records `.number` on regular bindings (`:count.number="x"`) as a coercion hint.
+    // Not standard Vue 2, the modifier is ignored when this is off.
+    pub compat_number_modifier: bool,
+    // records the source range of elements on ASTElement::range
+    pub output_source_range: bool,
+    // functional components may render several root nodes, every top-level element is kept
+    // and the render function returns them as an array
+    pub allow_multiple_roots: bool,
+    // name of the component the template belongs to, elements rendering it are marked as self references
+    pub component_name: Option<String>,
 
     pub is_pre_tag: Option<fn(tag: &str) -> bool>,
-    pub get_namespace: Option<fn(tag: &str) -> Option<&'static str>>,
+    pub is_unary_tag: Option<fn(tag: &str) -> bool>,
+    pub get_tag_namespace: Option<fn(tag: &str) -> Option<&'static str>>,
+    // rewrites component tag names, e.g. aliasing ui-button to UiButton. None keeps the tag as written
+    pub resolve_component: Option<fn(tag: &str) -> Option<String>>,
     pub warn: Option<Box<dyn WarnLogger>>,
     pub delimiters: Option<(String, String)>,
+    // supplementary named entities decoded in text and attribute values, keyed by the name
+    // without `&` and `;`. The standard html entities are always decoded
+    pub entities: Option<HashMap<String, String>>,
+    pub helpers: RenderHelpers,
 
     pub modules: Option<Vec<Box<dyn ModuleApi>>>,
+    // run before the structural directives (v-for, v-if, v-once) are processed
+    pub pre_transforms: Option<Vec<Box<dyn PreNodeTransform>>>,
+    // run while the element is processed, after the module transforms
+    pub transforms: Option<Vec<Box<dyn NodeTransform>>>,
+    // run when the element is closed, after it has been processed
+    pub post_transforms: Option<Vec<Box<dyn NodeTransform>>>,
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        CompilerOptions {
+            dev: false,
+            is_ssr: false,
+            v_bind_prop_short_hand: false,
+            preserve_comments: false,
+            whitespace_handling: WhitespaceHandling::default(),
+            trim_block_whitespace: false,
+            new_slot_syntax: true,
+            auto_key_v_for: false,
+            compat_number_modifier: false,
+            output_source_range: false,
+            allow_multiple_roots: false,
+            component_name: None,
+            is_pre_tag: None,
+            is_unary_tag: None,
+            get_tag_namespace: None,
+            resolve_component: None,
+            warn: None,
+            delimiters: None,
+            entities: None,
+            helpers: RenderHelpers::default(),
+            modules: None,
+            pre_transforms: None,
+            transforms: None,
+            post_transforms: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompilerOptionsError {
+    EmptyDelimiter,
+    IdenticalDelimiters(String),
+}
+
+impl fmt::Display for CompilerOptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompilerOptionsError::EmptyDelimiter => {
+                write!(
+                    f,
+                    "Invalid delimiters: open and close delimiters cannot be empty."
+                )
+            }
+            CompilerOptionsError::IdenticalDelimiters(delimiter) => write!(
+                f,
+                "Invalid delimiters: open and close delimiters cannot both be \"{}\".",
+                delimiter
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CompilerOptionsError {}
+
+impl CompilerOptions {
+    pub fn validate(&self) -> Result<(), CompilerOptionsError> {
+        if let Some((open, close)) = &self.delimiters {
+            if open.is_empty() || close.is_empty() {
+                return Err(CompilerOptionsError::EmptyDelimiter);
+            }
+            if open == close {
+                return Err(CompilerOptionsError::IdenticalDelimiters(open.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn builder() -> CompilerOptionsBuilder {
+        CompilerOptionsBuilder::default()
+    }
+}
+
+// Sets only the options that differ from the defaults, e.g.
+// CompilerOptions::builder().dev(true).web_modules().build()
+#[derive(Default)]
+pub struct CompilerOptionsBuilder {
+    options: CompilerOptions,
+}
+
+impl CompilerOptionsBuilder {
+    pub fn dev(mut self, dev: bool) -> Self {
+        self.options.dev = dev;
+        self
+    }
+
+    pub fn ssr(mut self, is_ssr: bool) -> Self {
+        self.options.is_ssr = is_ssr;
+        self
+    }
+
+    pub fn v_bind_prop_short_hand(mut self, enabled: bool) -> Self {
+        self.options.v_bind_prop_short_hand = enabled;
+        self
+    }
+
+    pub fn preserve_comments(mut self, preserve: bool) -> Self {
+        self.options.preserve_comments = preserve;
+        self
+    }
+
+    pub fn whitespace_handling(mut self, whitespace_handling: WhitespaceHandling) -> Self {
+        self.options.whitespace_handling = whitespace_handling;
+        self
+    }
+
+    pub fn trim_block_whitespace(mut self, enabled: bool) -> Self {
+        self.options.trim_block_whitespace = enabled;
+        self
+    }
+
+    pub fn new_slot_syntax(mut self, enabled: bool) -> Self {
+        self.options.new_slot_syntax = enabled;
+        self
+    }
+
+    pub fn auto_key_v_for(mut self, enabled: bool) -> Self {
+        self.options.auto_key_v_for = enabled;
+        self
+    }
+
+    pub fn compat_number_modifier(mut self, enabled: bool) -> Self {
+        self.options.compat_number_modifier = enabled;
+        self
+    }
+
+    pub fn output_source_range(mut self, enabled: bool) -> Self {
+        self.options.output_source_range = enabled;
+        self
+    }
+
+    pub fn allow_multiple_roots(mut self, enabled: bool) -> Self {
+        self.options.allow_multiple_roots = enabled;
+        self
+    }
+
+    pub fn component_name(mut self, name: &str) -> Self {
+        self.options.component_name = Some(name.to_string());
+        self
+    }
+
+    pub fn is_pre_tag(mut self, is_pre_tag: fn(tag: &str) -> bool) -> Self {
+        self.options.is_pre_tag = Some(is_pre_tag);
+        self
+    }
+
+    pub fn is_unary_tag(mut self, is_unary_tag: fn(tag: &str) -> bool) -> Self {
+        self.options.is_unary_tag = Some(is_unary_tag);
+        self
+    }
+
+    pub fn get_tag_namespace(
+        mut self,
+        get_tag_namespace: fn(tag: &str) -> Option<&'static str>,
+    ) -> Self {
+        self.options.get_tag_namespace = Some(get_tag_namespace);
+        self
+    }
+
+    pub fn resolve_component(mut self, resolve_component: fn(tag: &str) -> Option<String>) -> Self {
+        self.options.resolve_component = Some(resolve_component);
+        self
+    }
+
+    pub fn warn<F>(mut self, warn: F) -> Self
+    where
+        F: FnMut(&str) + Clone + 'static,
+    {
+        self.options.warn = Some(Box::new(warn));
+        self
+    }
+
+    pub fn warn_with_range<F>(mut self, warn: F) -> Self
+    where
+        F: FnMut(&str, Option<SourceRange>) + Clone + 'static,
+    {
+        self.options.warn = Some(Box::new(RangeWarnLogger(warn)));
+        self
+    }
+
+    pub fn delimiters(mut self, open: &str, close: &str) -> Self {
+        self.options.delimiters = Some((open.to_string(), close.to_string()));
+        self
+    }
+
+    pub fn entities(mut self, entities: HashMap<String, String>) -> Self {
+        self.options.entities = Some(entities);
+        self
+    }
+
+    pub fn helpers(mut self, helpers: RenderHelpers) -> Self {
+        self.options.helpers = helpers;
+        self
+    }
+
+    pub fn module(mut self, module: Box<dyn ModuleApi>) -> Self {
+        self.options
+            .modules
+            .get_or_insert_with(Vec::new)
+            .push(module);
+        self
+    }
+
+    // the class, style and v-model modules of the web platform
+    pub fn web_modules(self) -> Self {
+        self.module(Box::new(ClassModule {}))
+            .module(Box::new(ModelModule {}))
+            .module(Box::new(StyleModule {}))
+    }
+
+    pub fn pre_transform(mut self, transform: Box<dyn PreNodeTransform>) -> Self {
+        self.options
+            .pre_transforms
+            .get_or_insert_with(Vec::new)
+            .push(transform);
+        self
+    }
+
+    pub fn transform(mut self, transform: Box<dyn NodeTransform>) -> Self {
+        self.options
+            .transforms
+            .get_or_insert_with(Vec::new)
+            .push(transform);
+        self
+    }
+
+    pub fn post_transform(mut self, transform: Box<dyn NodeTransform>) -> Self {
+        self.options
+            .post_transforms
+            .get_or_insert_with(Vec::new)
+            .push(transform);
+        self
+    }
+
+    pub fn build(self) -> Result<CompilerOptions, CompilerOptionsError> {
+        self.options.validate()?;
+
+        Ok(self.options)
+    }
+}
+
+pub trait PreNodeTransform {
+    // The returned node replaces the transformed node, the replaced node is removed from the tree
+    fn transform(
+        &self,
+        node: &mut ASTNode,
+        tree: &mut ASTTree,
+        options: &CompilerOptions,
+    ) -> Option<Rc<RefCell<ASTNode>>>;
+}
+
+pub trait NodeTransform {
+    fn transform(&self, node: &mut ASTNode, tree: &mut ASTTree, options: &CompilerOptions);
 }
 
 pub trait ModuleApi {
@@ -106,29 +451,67 @@ fn is_forbidden_tag(el: &Token) -> bool {
                 }
             }
 
+            // a bound type is only known not to be javascript when it is a string literal
+            if let Some(bound_entry) = get_bound_attribute_value(el, &UC_TYPE) {
+                let exp = bound_entry.map(|(val, _quotes)| val).unwrap_or_default();
+                let exp = exp.trim();
+                let is_string_literal = exp.len() >= 2
+                    && ["'", "\"", "`"]
+                        .iter()
+                        .any(|quote| exp.starts_with(quote) && exp.ends_with(quote));
+
+                return !is_string_literal || &exp[1..exp.len() - 1] == "text/javascript";
+            }
+
             return false;
         }
         _ => false,
     }
 }
 
+fn is_whitespace_text(child: &Rc<RefCell<ASTNode>>) -> bool {
+    let child = child.borrow();
+    child.el.kind == ASTElementKind::Text
+        && !child.el.is_comment
+        && child.el.token.data.trim().is_empty()
+}
+
+// suggests the intended attribute name for directives written with a wrong prefix,
+// e.g. `:v-if` -> `v-if` and `v-:foo` -> `:foo`
+fn misplaced_directive_suggestion(name: &str) -> Option<String> {
+    if let Some(captures) = MISPLACED_DIRECTIVE_RE.captures(name) {
+        return Some(captures[1].to_string());
+    }
+
+    EMPTY_DIRECTIVE_NAME_RE
+        .captures(name)
+        .map(|captures| format!(":{}", &captures[1]))
+}
+
 pub struct VueParser<'a> {
     dev: bool,
     warn: Box<dyn WarnLogger>,
+    // the warnings when CompilerOptions::warn is not set, moved to ASTTree::warnings after parsing
+    collected_warnings: Option<Rc<RefCell<Vec<String>>>>,
 
     is_ssr: bool,
     is_pre_tag: fn(tag: &str) -> bool,
+    is_unary_tag: fn(tag: &str) -> bool,
 
     preserve_comments: bool,
     whitespace_handling: WhitespaceHandling,
 
-    in_v_pre: bool,
-    in_pre: bool,
+    // the element carrying v-pre, v-pre on its descendants has no further effect
+    v_pre_id: Option<usize>,
+    // pre tags can be nested, whitespace is kept until the outermost one is closed
+    pre_depth: usize,
     warned: bool,
 
-    get_namespace: fn(tag: &str) -> Option<&'static str>,
+    get_tag_namespace: fn(tag: &str) -> Option<&'static str>,
 
     active_text: Option<String>,
+    // the source of the pending text, only tracked with CompilerOptions::output_source_range
+    active_text_range: Option<SourceRange>,
     options: &'a CompilerOptions,
 }
 
@@ -142,23 +525,44 @@ const PARSER_OPTIONS: ParserOptions = ParserOptions {
 };
 
 impl<'a> VueParser<'a> {
+    // panics when the options are invalid, see try_new
     pub fn new(options: &'a CompilerOptions) -> VueParser<'a> {
-        VueParser {
+        match VueParser::try_new(options) {
+            Ok(parser) => parser,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    pub fn try_new(options: &'a CompilerOptions) -> Result<VueParser<'a>, CompilerOptionsError> {
+        options.validate()?;
+
+        let (warn, collected_warnings) = match &options.warn {
+            Some(warn) => (warn.clone(), None),
+            None => {
+                let (warn, warnings) = collecting_warn_logger();
+                (warn, Some(warnings))
+            }
+        };
+
+        Ok(VueParser {
             options: &options,
             dev: options.dev,
             is_pre_tag: options
                 .is_pre_tag
                 .unwrap_or(|t| t.eq_ignore_ascii_case("pre")),
+            is_unary_tag: options.is_unary_tag.unwrap_or(is_unary_tag),
             is_ssr: options.is_ssr,
-            in_v_pre: false,
-            in_pre: false,
+            v_pre_id: None,
+            pre_depth: 0,
             warned: false,
-            get_namespace: options.get_namespace.unwrap_or(get_namespace),
+            get_tag_namespace: options.get_tag_namespace.unwrap_or(get_tag_namespace),
             whitespace_handling: options.whitespace_handling,
-            preserve_comments: false,
+            preserve_comments: options.preserve_comments,
             active_text: None,
-            warn: options.warn.clone().unwrap_or_else(|| Box::new(print_line)),
-        }
+            active_text_range: None,
+            warn,
+            collected_warnings,
+        })
     }
 
     fn warn_once(&mut self, msg: &str) {
@@ -186,49 +590,168 @@ impl<'a> VueParser<'a> {
         }
     }
 
+    // <keep-alive> only caches its first child component, the other children are never rendered
+    fn check_keep_alive_children(&mut self, node: &ASTNode) {
+        if !node.el.token.data.eq_ignore_ascii_case("keep-alive") {
+            return;
+        }
+
+        let element_children = node
+            .children
+            .iter()
+            .filter(|child| child.borrow().el.kind == ASTElementKind::Element)
+            .count();
+        if element_children > 1 {
+            self.warn.call_with_range(
+                "<keep-alive> can only be used on a single element. Only its first child is rendered and cached.",
+                node.el.range,
+            );
+        }
+    }
+
+    // the deprecated slot attributes on some children and v-slot on others leave it unclear
+    // which syntax resolves the slots of the component
+    fn check_mixed_slot_syntax(&mut self, node: &ASTNode) {
+        let Some(scoped_slots) = &node.el.scoped_slots else {
+            return;
+        };
+
+        let mut has_v_slot = false;
+        let mut has_legacy_slot = node
+            .children
+            .iter()
+            .any(|child| child.borrow().el.slot_target.is_some());
+        for slot in scoped_slots.values() {
+            if slot.borrow().el.v_slot {
+                has_v_slot = true;
+            } else {
+                has_legacy_slot = true;
+            }
+        }
+
+        if has_v_slot && has_legacy_slot {
+            self.warn.call_with_range(
+                &format!(
+                    "<{}>: Unexpected mixed usage of different slot syntaxes. Use either v-slot or the deprecated slot and slot-scope attributes for all the slots of a component.",
+                    node.el.token.data
+                ),
+                node.el.range,
+            );
+        }
+    }
+
     pub fn parse(&mut self, template: &str) -> ASTTree {
-        let parser = Parser::new(template, &PARSER_OPTIONS);
+        let _parse_span = phase_span!("parse");
+        // a byte order mark left over from reading the template file is not content,
+        // source ranges are relative to the template without it
+        let template = template.strip_prefix('\u{feff}').unwrap_or(template);
+        let source_scan = scan_source(
+            template,
+            &PARSER_OPTIONS.tokenizer_options,
+            self.options.entities.as_ref(),
+        );
+        let mut open_tag_sources = source_scan.open_tags.into_iter();
+        let mut cdata_comments = source_scan.cdata_comments.into_iter();
+        let mut close_tag_sources = source_scan.close_tags.into_iter();
+        let mut text_sources = source_scan.texts.into_iter();
+        // elements the parser closed implicitly, reported unless an opened element closed them
+        let mut unclosed: Vec<(Box<str>, Option<SourceRange>)> = Vec::new();
+        let line_index = LineIndex::new(template);
         let is_dev = self.dev;
+        self.v_pre_id = None;
+        self.pre_depth = 0;
 
         let mut root_tree: ASTTree = ASTTree::new(is_dev, self.warn.clone_box());
         let mut stack: VecDeque<usize> = VecDeque::new();
         let mut current_parent_id = 0;
-        let mut current_namespace: Option<&'static str> = None;
 
-        for token in parser {
+        for mut token in Parser::new(template, &PARSER_OPTIONS) {
+            // CDATA sections are emitted as comments, their content is plain text in html
+            let mut text_source = None;
+            if token.kind == TokenKind::Comment && cdata_comments.next().unwrap_or(false) {
+                token.kind = TokenKind::Text;
+            } else if token.kind == TokenKind::Text {
+                text_source = text_sources.next();
+            }
+
+            // the supplementary entities are looked up in the text as written, not in the ones
+            // the tokenizer decoded
+            if let (Some(entities), Some(TextSource { entity: false, .. })) =
+                (&self.options.entities, text_source)
+            {
+                if let Cow::Owned(decoded) = decode_custom_entities(&token.data, entities) {
+                    token.data = decoded.into();
+                }
+            }
+
             match token.kind {
                 TokenKind::OpenTag => {
                     self.end_text_node(&mut root_tree, current_parent_id);
+                    // e.g. the previous <li> or a <p> before a block element, closed as html expects
+                    unclosed.clear();
+
+                    let mut el = create_ast_element(token, ASTElementKind::Element);
+                    if !el.token.is_implied {
+                        if let Some(open_tag_source) = open_tag_sources.next() {
+                            el.attr_names = open_tag_source.attr_names;
+                            if let Some(attrs) = &mut el.token.attrs {
+                                for (name, decoded) in open_tag_source.attr_values {
+                                    if let Some(Some((value, _quote_type))) = attrs.get_mut(name) {
+                                        *value = decoded.into();
+                                    }
+                                }
+                            }
+                            if self.options.output_source_range {
+                                for (name, start, end) in open_tag_source.attr_value_ranges {
+                                    el.attr_ranges.insert(name, line_index.range(start, end));
+                                }
+                                el.range = Some(
+                                    line_index.range(open_tag_source.start, open_tag_source.end),
+                                );
+                            }
+                        }
+                    }
 
-                    let mut node_rc = root_tree.create(
-                        create_ast_element(token, ASTElementKind::Element),
-                        current_parent_id,
-                        is_dev,
-                        self.warn.clone_box(),
-                    );
+                    let mut node_rc =
+                        root_tree.create(el, current_parent_id, is_dev, self.warn.clone_box());
                     let mut node = node_rc.borrow_mut();
-                    let node_id = node.id;
-                    root_tree.set(node_id, node_rc.clone());
 
-                    let ns = if let Some(parent_ns) = current_namespace {
-                        Some(parent_ns)
-                    } else {
-                        (self.get_namespace)(&node.el.token.data)
+                    // inherit parent ns if there is one, foreignObject switches
+                    // its descendants back to the html namespace
+                    let parent_ns = {
+                        let parent = root_tree.get(current_parent_id).unwrap();
+                        let parent = parent.borrow();
+                        if parent.el.token.data.eq_ignore_ascii_case("foreignObject") {
+                            None
+                        } else {
+                            parent.el.ns.clone()
+                        }
                     };
-
-                    if let Some(namespace) = ns {
-                        node.el.ns = Some(namespace);
-                        current_namespace = Some(namespace);
-                    }
+                    node.el.ns = parent_ns.or_else(|| {
+                        (self.get_tag_namespace)(&node.el.token.data).map(str::to_string)
+                    });
 
                     if is_dev {
                         if let Some(attrs) = &node.el.token.attrs {
                             for (attr_key, _attr_value) in attrs {
-                                if INVALID_ATTRIBUTE_RE.find(&attr_key).is_some() {
-                                    self.warn.call(
-                                        "Invalid dynamic argument expression: attribute names cannot contain spaces, quotes, <, >, / or =."
+                                if INVALID_ATTRIBUTE_RE.is_match(attr_key)
+                                    || UNCLOSED_DYNAMIC_ARG_RE.is_match(attr_key)
+                                {
+                                    self.warn.call_with_range(
+                                        "Invalid dynamic argument expression: attribute names cannot contain spaces, quotes, <, >, / or =.",
+                                        node.el.range,
                                     )
                                 }
+                                if !self.in_v_pre() {
+                                    if let Some(suggestion) =
+                                        misplaced_directive_suggestion(attr_key)
+                                    {
+                                        self.warn.call_with_range(&format!(
+                                            "Attribute \"{}\" looks like a directive with a wrong prefix, did you mean \"{}\"?",
+                                            attr_key, suggestion
+                                        ), node.el.range)
+                                    }
+                                }
                             }
                         }
                     }
@@ -237,14 +760,10 @@ impl<'a> VueParser<'a> {
                         node.el.forbidden = true;
 
                         if is_dev {
-                            // TODO: add tag
-                            self.warn.call(
-                                "
-            Templates should only be responsible for mapping the state to the
-            UI. Avoid placing tags with side-effects in your templates, such as
-            <{tag}> as they will not be parsed.
-                ",
-                            )
+                            self.warn.call_with_range(&format!(
+                                "Templates should only be responsible for mapping the state to the UI. Avoid placing tags with side-effects in your templates, such as <{}>, as they will not be parsed.",
+                                node.el.token.data
+                            ), node.el.range)
                         }
                     }
 
@@ -254,172 +773,142 @@ impl<'a> VueParser<'a> {
                                 module.pre_transform_node(&mut node, &mut root_tree, self.options);
 
                             if let Some(new_node) = possibly_new_node {
+                                let replaced_id = node.id;
+                                drop(node);
+                                if new_node.borrow().id != replaced_id {
+                                    root_tree.remove(replaced_id);
+                                }
+                                node_rc = new_node;
+                                node = node_rc.borrow_mut();
+                            }
+                        }
+                    }
+                    if let Some(pre_transforms) = &self.options.pre_transforms {
+                        for pre_transform in pre_transforms {
+                            let possibly_new_node =
+                                pre_transform.transform(&mut node, &mut root_tree, self.options);
+
+                            if let Some(new_node) = possibly_new_node {
+                                let replaced_id = node.id;
                                 drop(node);
+                                if new_node.borrow().id != replaced_id {
+                                    root_tree.remove(replaced_id);
+                                }
                                 node_rc = new_node;
                                 node = node_rc.borrow_mut();
                             }
                         }
                     }
+                    let node_id = node.id;
 
-                    if !self.in_v_pre {
+                    if !self.in_v_pre() {
                         node.process_pre();
                         if node.el.pre {
-                            self.in_v_pre = true;
+                            self.v_pre_id = Some(node.id);
                         }
                     }
                     if (self.is_pre_tag)(&node.el.token.data) {
-                        self.in_pre = true;
+                        self.pre_depth += 1;
                     }
-                    if self.in_v_pre {
+                    if self.in_v_pre() {
                         node.process_raw_attributes()
                     } else if !node.el.processed {
+                        let _process_span = phase_span!("process");
                         node.process_for();
                         node.process_if();
                         node.process_once();
                     }
 
-                    current_parent_id = node_id;
-                    stack.push_back(node_id);
+                    root_tree.stats.max_depth = root_tree.stats.max_depth.max(stack.len() + 1);
+
+                    if (self.is_unary_tag)(&node.el.token.data) {
+                        drop(node);
+                        self.close_element(&mut root_tree, &node_rc, stack.is_empty());
+                    } else {
+                        current_parent_id = node_id;
+                        stack.push_back(node_id);
+                    }
                 }
                 TokenKind::CloseTag => {
                     self.end_text_node(&mut root_tree, current_parent_id);
 
-                    let current_open_tag_id = stack.pop_back();
-                    current_parent_id = *stack.back().unwrap_or(&(0usize));
+                    // unary elements are closed as soon as they are opened
+                    if (self.is_unary_tag)(&token.data) {
+                        continue;
+                    }
 
-                    if let Some(open_tag_id) = current_open_tag_id {
-                        let node_ptr = root_tree.get(open_tag_id).unwrap();
-                        let mut node = node_ptr.borrow_mut();
-                        // trim white space ??
+                    // the parser inserts implied close tags for the element it closes implicitly,
+                    // which is always the current one. Otherwise find the closest opened element
+                    // of the same type
+                    let matching_position = if token.is_implied {
+                        stack.len().checked_sub(1)
+                    } else {
+                        stack.iter().rposition(|open_tag_id| {
+                            let open_node = root_tree.get(*open_tag_id).unwrap().borrow();
 
-                        if !self.in_v_pre && !node.el.processed {
-                            node.process_element(&root_tree, &self.options);
-                        }
-                        // tree management
-                        if stack.is_empty() && node.id != 1 {
-                            if root_tree.get(1).unwrap().borrow().el.if_val.is_some()
-                                && (node.el.else_if_val.is_some() || node.el.is_else)
-                            {
-                                if is_dev {
-                                    self.check_root_constraints(&node.el);
-                                }
-                                let else_if_val = node.el.else_if_val.clone();
-                                let node_id = node.id;
-
-                                node.add_if_condition(IfCondition {
-                                    exp: else_if_val,
-                                    block_id: node_id,
-                                });
-                            } else if is_dev {
-                                self.warn.call("Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.");
-                            }
-                        }
-                        let mut current_parent =
-                            root_tree.get(node.parent_id).unwrap().borrow_mut();
+                            open_node.el.kind == ASTElementKind::Element
+                                && open_node.el.token.data.eq_ignore_ascii_case(&token.data)
+                        })
+                    };
 
-                        // always take root node, even if forbidden
-                        if !node.el.forbidden || node.id == 1 {
-                            if is_dev && node.id == 1 {
-                                self.check_root_constraints(&node.el);
-                            }
-                            if node.el.else_if_val.is_some() || node.el.is_else {
-                                node.process_if_conditions(
-                                    node_ptr,
-                                    current_parent.children.as_mut(),
+                    let mut close_tag_source = None;
+                    if !token.is_implied {
+                        if is_dev {
+                            // an element left open inside the one this tag closes
+                            for (tag, range) in unclosed.drain(..) {
+                                self.warn.call_with_range(
+                                    &format!("tag <{}> has no matching end tag.", tag),
+                                    range,
                                 );
-                            } else {
-                                if node.el.slot_scope.is_some() {
-                                    // scoped slot
-                                    // keep it in the children list so that v-else(-if) conditions can
-                                    // find it as the prev node.
-                                    let scoped_slots = current_parent
-                                        .el
-                                        .scoped_slots
-                                        .get_or_insert(UniCaseBTreeMap::new());
-
-                                    let slot_target = node.el.slot_target.clone();
-                                    let name = if let Some(slot_target) = slot_target {
-                                        slot_target
-                                    } else {
-                                        "\"default\"".to_string()
-                                    };
-
-                                    scoped_slots.insert(name, node_ptr.clone());
-                                }
-
-                                let children: &mut Vec<Rc<RefCell<ASTNode>>> =
-                                    current_parent.children.as_mut();
-                                children.push(node_ptr.clone());
-
-                                // TODO: This is most likely unnecessary, verify later
-                                if current_parent.id != current_parent_id {
-                                    println!("parent id does not match current parent id");
-                                }
                             }
                         }
+                        close_tag_source = self.next_close_tag_source(
+                            &mut close_tag_sources,
+                            Some(&token.data),
+                            &line_index,
+                        );
+                    }
 
-                        // final children cleanup
-                        // filter out scoped slots
-                        node.children = node
-                            .children
-                            .iter()
-                            .map(|child| Rc::clone(child))
-                            .filter_map(|child_rc| {
-                                let child = child_rc.borrow_mut();
-                                if child.el.slot_scope.is_none() {
-                                    Some(Rc::clone(&child_rc))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect::<Vec<_>>();
+                    let Some(position) = matching_position else {
+                        continue;
+                    };
 
-                        // remove trailing whitespace node again
+                    // close all the open elements, up the stack
+                    while stack.len() > position {
+                        let open_tag_id = stack.pop_back().unwrap();
+                        current_parent_id = *stack.back().unwrap_or(&(0usize));
 
-                        if node.el.pre {
-                            self.in_v_pre = false
+                        let node_ptr = root_tree.get(open_tag_id).unwrap().clone();
+                        // the element ends with the close tag, implied ones keep their start tag
+                        if let Some(close_tag) = &close_tag_source {
+                            if let Some(range) = &mut node_ptr.borrow_mut().el.range {
+                                range.end = close_tag.end;
+                            }
                         }
-                        if (self.is_pre_tag)(&node.el.token.data) {
-                            self.in_pre = false
+                        if token.is_implied {
+                            let open_node = node_ptr.borrow();
+                            unclosed.push((open_node.el.token.data.clone(), open_node.el.range));
                         }
-
-                        // apply post-transforms
-                        // for (let i = 0; i < postTransforms.length; i++) {
-                        //     postTransforms[i](element, options)
-                        // }
+                        self.close_element(&mut root_tree, &node_ptr, stack.is_empty());
                     }
                 }
                 TokenKind::Comment => {
-                    if !self.preserve_comments {
-                        continue;
-                    }
-                    if current_parent_id != 0 {
-                        self.end_text_node(&mut root_tree, current_parent_id);
-
-                        let node_rc = root_tree.create(
-                            create_ast_element(token, ASTElementKind::Text),
-                            current_parent_id,
-                            is_dev,
-                            self.warn.clone_box(),
-                        );
-                        let mut node = node_rc.borrow_mut();
-                        node.el.is_comment = true;
-                        root_tree.set(node.id, node_rc.clone());
-                        current_parent_id = node.id;
-                        stack.push_back(node.id);
-                    }
-                }
-                TokenKind::CommentEnd => {
-                    if !self.preserve_comments {
+                    // comments outside the root element are always dropped
+                    if !self.preserve_comments || current_parent_id == 0 {
                         continue;
                     }
+                    self.end_text_node(&mut root_tree, current_parent_id);
 
-                    if current_parent_id != 0 {
-                        self.end_text_node(&mut root_tree, current_parent_id);
+                    let node_rc = root_tree.create(
+                        create_ast_element(token, ASTElementKind::Text),
+                        current_parent_id,
+                        is_dev,
+                        self.warn.clone_box(),
+                    );
+                    node_rc.borrow_mut().el.is_comment = true;
 
-                        let _unused_open_comment_id = stack.pop_back();
-                        current_parent_id = *stack.back().unwrap_or(&(0usize));
-                    }
+                    let parent = root_tree.get(current_parent_id).unwrap();
+                    parent.borrow_mut().children.push(node_rc);
                 }
                 TokenKind::Text => {
                     if current_parent_id == 0 {
@@ -444,6 +933,15 @@ impl<'a> VueParser<'a> {
                     let text = self.condense_whitespace(&root_tree, current_parent_id, &token.data);
 
                     if !text.is_empty() {
+                        let output_source_range = self.options.output_source_range;
+                        if let Some(TextSource { start, end, .. }) =
+                            text_source.filter(|_| output_source_range)
+                        {
+                            match &mut self.active_text_range {
+                                Some(range) => range.end = end,
+                                None => self.active_text_range = Some(line_index.range(start, end)),
+                            }
+                        }
                         if let Some(active_text) = &mut self.active_text {
                             *active_text += &text;
                         } else {
@@ -451,31 +949,204 @@ impl<'a> VueParser<'a> {
                         }
                     }
                 }
-                _ => {}
+                // doctype and processing instructions have no meaning in a template
+                TokenKind::ProcessingInstruction | TokenKind::CommentEnd => {}
+            }
+        }
+
+        if is_dev {
+            // the elements still open at the end of the template
+            for (tag, range) in unclosed {
+                self.warn
+                    .call_with_range(&format!("tag <{}> has no matching end tag.", tag), range);
+            }
+            self.next_close_tag_source(&mut close_tag_sources, None, &line_index);
+        }
+
+        if let Some(warnings) = &self.collected_warnings {
+            root_tree.warnings = warnings.take();
+        }
+
+        root_tree
+    }
+
+    // consumes the close tags of the template up to the one the parser closed an element with
+    // and returns it, the ones skipped on the way were dropped by the parser because nothing
+    // was open and are warned about in dev
+    fn next_close_tag_source(
+        &mut self,
+        close_tag_sources: &mut impl Iterator<Item = CloseTagSource>,
+        closed_tag: Option<&str>,
+        line_index: &LineIndex,
+    ) -> Option<CloseTagSource> {
+        for close_tag in close_tag_sources {
+            if closed_tag.is_some_and(|tag| tag.eq_ignore_ascii_case(&close_tag.name)) {
+                return Some(close_tag);
+            }
+            // a close tag of a void element is ignored, as in html
+            if self.dev && !(self.is_unary_tag)(&close_tag.name) {
+                let range = if self.options.output_source_range {
+                    Some(line_index.range(close_tag.start, close_tag.end))
+                } else {
+                    None
+                };
+                self.warn.call_with_range(
+                    &format!("tag </{}> has no matching start tag.", close_tag.name),
+                    range,
+                );
             }
         }
 
+        None
+    }
+
+    fn close_element(
+        &mut self,
+        root_tree: &mut ASTTree,
+        node_ptr: &Rc<RefCell<ASTNode>>,
+        is_root_level: bool,
+    ) {
+        let is_dev = self.dev;
+        let mut node = node_ptr.borrow_mut();
+        self.trim_boundary_whitespace(root_tree, &mut node);
+
+        if !self.in_v_pre() && !node.el.processed {
+            let _process_span = phase_span!("process");
+            node.process_element(root_tree, self.options);
+        }
         root_tree
+            .stats
+            .filters
+            .extend(node.el.filters.iter().cloned());
+
+        // tree management
+        let mut is_extra_root = false;
+        if is_root_level {
+            match root_tree.root_id {
+                None => {
+                    root_tree.root_id = Some(node.id);
+                    if is_dev && !self.options.allow_multiple_roots {
+                        self.check_root_constraints(&node.el);
+                    }
+                }
+                // every top-level element is kept, v-else(-if) still chains to its previous sibling
+                Some(_) if self.options.allow_multiple_roots => {}
+                Some(root_id) => {
+                    // allow root elements with v-if, v-else-if and v-else
+                    if root_tree.get(root_id).unwrap().borrow().el.if_val.is_some()
+                        && (node.el.else_if_val.is_some() || node.el.is_else)
+                    {
+                        if is_dev {
+                            self.check_root_constraints(&node.el);
+                        }
+                    } else {
+                        is_extra_root = true;
+                        if is_dev {
+                            self.warn.call_with_range("Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.", node.el.range);
+                        }
+                    }
+                }
+            }
+        }
+        let mut current_parent = root_tree.get(node.parent_id).unwrap().borrow_mut();
+        let is_root = root_tree.root_id == Some(node.id);
+
+        // always take root node, even if forbidden
+        if !is_extra_root && (!node.el.forbidden || is_root) {
+            if node.el.else_if_val.is_some() || node.el.is_else {
+                node.process_if_conditions(node_ptr, current_parent.children.as_mut());
+            } else {
+                if node.el.slot_scope.is_some() {
+                    // scoped slot
+                    // keep it in the children list so that v-else(-if) conditions can
+                    // find it as the prev node.
+                    let scoped_slots = current_parent
+                        .el
+                        .scoped_slots
+                        .get_or_insert(UniCaseBTreeMap::new());
+
+                    let slot_target = node.el.slot_target.clone();
+                    let name = if let Some(slot_target) = slot_target {
+                        slot_target
+                    } else {
+                        "\"default\"".to_string()
+                    };
+
+                    scoped_slots.insert(name, node_ptr.clone());
+                }
+
+                let children: &mut Vec<Rc<RefCell<ASTNode>>> = current_parent.children.as_mut();
+                children.push(node_ptr.clone());
+            }
+        }
+
+        drop(current_parent);
+
+        // final children cleanup
+        // filter out scoped slots
+        node.children = node
+            .children
+            .iter()
+            .map(Rc::clone)
+            .filter_map(|child_rc| {
+                let child = child_rc.borrow_mut();
+                if child.el.slot_scope.is_none() {
+                    Some(Rc::clone(&child_rc))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // remove trailing whitespace node again
+        self.trim_boundary_whitespace(root_tree, &mut node);
+        self.trim_block_whitespace(root_tree, &mut node);
+
+        if is_dev {
+            self.check_keep_alive_children(&node);
+            self.check_mixed_slot_syntax(&node);
+        }
+
+        if self.v_pre_id == Some(node.id) {
+            self.v_pre_id = None;
+        }
+        if (self.is_pre_tag)(&node.el.token.data) {
+            self.pre_depth = self.pre_depth.saturating_sub(1);
+        }
+
+        // apply post-transforms
+        if let Some(post_transforms) = &self.options.post_transforms {
+            for post_transform in post_transforms {
+                post_transform.transform(&mut node, root_tree, self.options);
+            }
+        }
+
+        let normalization_type = node.compute_normalization_type(root_tree);
+        node.el.normalization_type = normalization_type;
     }
 
     fn end_text_node(&mut self, root_tree: &mut ASTTree, current_parent_id: usize) {
         if let Some(active_text) = &self.active_text {
-            let parse_text_result: Option<(String, Vec<String>)>;
-            let final_text = if self.whitespace_handling == WhitespaceHandling::Condense {
-                WHITESPACE_RE.replace_all(active_text, " ").to_string()
-            } else {
-                active_text.to_string()
-            };
+            let parse_text_result: Option<(String, Vec<String>, Vec<String>)>;
+            let final_text =
+                if !self.in_pre() && self.whitespace_handling == WhitespaceHandling::Condense {
+                    WHITESPACE_RE.replace_all(active_text, " ").to_string()
+                } else {
+                    active_text.to_string()
+                };
 
             if !&final_text.is_empty() {
-                if !self.in_v_pre {
-                    parse_text_result = parse_text(&final_text, &self.options.delimiters);
+                if !self.in_v_pre() {
+                    parse_text_result = parse_text(
+                        &final_text,
+                        &self.options.delimiters,
+                        &self.options.helpers.to_string,
+                    );
                 } else {
                     parse_text_result = None;
                 }
 
                 let node_rc: Rc<RefCell<ASTNode>>;
-                let mut node: RefMut<ASTNode>;
                 if let Some(expression_text) = parse_text_result {
                     node_rc = root_tree.create(
                         create_ast_element(
@@ -491,9 +1162,14 @@ impl<'a> VueParser<'a> {
                         self.dev,
                         self.warn.clone_box(),
                     );
-                    node = node_rc.borrow_mut();
+                    let mut node = node_rc.borrow_mut();
                     node.el.expression = Some(expression_text.0);
                     node.el.tokens = Some(expression_text.1);
+                    root_tree
+                        .stats
+                        .filters
+                        .extend(expression_text.2.iter().cloned());
+                    node.el.filters = expression_text.2;
                 } else {
                     node_rc = root_tree.create(
                         create_ast_element(
@@ -509,20 +1185,78 @@ impl<'a> VueParser<'a> {
                         self.dev,
                         self.warn.clone_box(),
                     );
-                    node = node_rc.borrow_mut();
                 }
 
+                {
+                    let mut node = node_rc.borrow_mut();
+                    node.el.in_pre = self.in_pre();
+                    node.el.range = self.active_text_range;
+                }
                 root_tree
                     .get(current_parent_id)
                     .unwrap()
                     .borrow_mut()
                     .children
                     .push(node_rc.clone());
-                root_tree.set(node.id, node_rc.clone());
             }
 
             self.active_text = None;
         }
+        self.active_text_range = None;
+    }
+
+    fn in_v_pre(&self) -> bool {
+        self.v_pre_id.is_some()
+    }
+
+    fn in_pre(&self) -> bool {
+        self.pre_depth > 0
+    }
+
+    // drops whitespace-only text children at the start and end of an element, kept inside <pre>
+    fn trim_boundary_whitespace(&self, root_tree: &mut ASTTree, node: &mut ASTNode) {
+        if self.in_pre() {
+            return;
+        }
+
+        while node.children.last().is_some_and(is_whitespace_text) {
+            let child = node.children.pop().unwrap();
+            root_tree.remove(child.borrow().id);
+        }
+        while node.children.first().is_some_and(is_whitespace_text) {
+            let child = node.children.remove(0);
+            root_tree.remove(child.borrow().id);
+        }
+    }
+
+    // drops whitespace-only text children next to a block-level element, see
+    // CompilerOptions::trim_block_whitespace
+    fn trim_block_whitespace(&self, root_tree: &mut ASTTree, node: &mut ASTNode) {
+        if !self.options.trim_block_whitespace
+            || self.in_pre()
+            || self.whitespace_handling != WhitespaceHandling::Condense
+        {
+            return;
+        }
+
+        let is_block = |child: Option<&Rc<RefCell<ASTNode>>>| {
+            child.is_some_and(|child| {
+                let child = child.borrow();
+                child.el.kind == ASTElementKind::Element && is_block_tag(&child.el.token.data)
+            })
+        };
+
+        let children = std::mem::take(&mut node.children);
+        for (index, child) in children.iter().enumerate() {
+            if is_whitespace_text(child)
+                && (is_block(index.checked_sub(1).and_then(|prev| children.get(prev)))
+                    || is_block(children.get(index + 1)))
+            {
+                root_tree.remove(child.borrow().id);
+            } else {
+                node.children.push(Rc::clone(child));
+            }
+        }
     }
 
     fn condense_whitespace(
@@ -531,7 +1265,7 @@ impl<'a> VueParser<'a> {
         current_parent_id: usize,
         text: &str,
     ) -> String {
-        return if self.in_pre {
+        return if self.in_pre() {
             text.to_string()
         } else if !text.trim().is_empty() {
             if self.whitespace_handling == WhitespaceHandling::Condense {
@@ -563,3 +1297,29 @@ impl<'a> VueParser<'a> {
         };
     }
 }
+
+pub struct CompileResult {
+    pub ast: ASTTree,
+    pub render: String,
+    pub static_render_fns: Vec<String>,
+    // warnings raised while compiling, only collected when CompilerOptions::warn is not set
+    pub errors: Vec<String>,
+}
+
+// parses, optimizes and generates the render code of a template in one go, like compile of vue-template-compiler.
+// panics when the options are invalid, see CompilerOptions::validate
+pub fn compile(template: &str, options: CompilerOptions) -> CompileResult {
+    let mut ast = VueParser::new(&options).parse(template);
+    optimizer::optimize(&mut ast);
+    let code = codegen::generate(&ast, &options);
+
+    let mut errors = std::mem::take(&mut ast.warnings);
+    errors.extend(code.warnings);
+
+    CompileResult {
+        ast,
+        render: code.render,
+        static_render_fns: code.static_render_fns,
+        errors,
+    }
+}
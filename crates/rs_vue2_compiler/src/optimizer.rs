@@ -0,0 +1,176 @@
+use crate::ast_tree::{ASTElementKind, ASTNode, ASTTree};
+use crate::trace::phase_span;
+use crate::web::element::is_reserved_tag;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Goal of the optimizer: walk the generated template AST tree
+// and detect sub-trees that are purely static, i.e. parts of
+// the DOM that never needs to change.
+//
+// Once we detect these sub-trees, we can:
+//
+// 1. Hoist them into constants, so that we no longer need to
+//    create fresh nodes for them on each re-render;
+// 2. Completely skip them in the patching process.
+pub fn optimize(tree: &mut ASTTree) {
+    let _optimize_span = phase_span!("optimize");
+
+    // every top-level element is a root with CompilerOptions::allow_multiple_roots
+    let roots: Vec<Rc<RefCell<ASTNode>>> = tree.wrapper.borrow().children.to_vec();
+
+    for root in &roots {
+        mark_static(tree, root);
+    }
+    tree.stats.static_roots = roots
+        .iter()
+        .map(|root| mark_static_roots(tree, root, false))
+        .sum();
+}
+
+fn mark_static(tree: &ASTTree, node_ptr: &Rc<RefCell<ASTNode>>) {
+    let is_static = is_static(&node_ptr.borrow());
+    node_ptr.borrow_mut().el.is_static = is_static;
+
+    let node = node_ptr.borrow();
+    if node.el.kind != ASTElementKind::Element {
+        return;
+    }
+
+    // do not make component slot content static. this avoids
+    // 1. components not able to mutate slot nodes
+    // 2. static slot content fails for hot-reloading
+    if !is_reserved_tag(&node.el.token.data)
+        && !node.el.token.data.eq_ignore_ascii_case("slot")
+        && !node.el.inline_template
+    {
+        return;
+    }
+
+    let children: Vec<Rc<RefCell<ASTNode>>> = node.children.to_vec();
+    let blocks = if_condition_blocks(tree, &node);
+    drop(node);
+
+    for child in children.iter().chain(blocks.iter()) {
+        mark_static(tree, child);
+        if !child.borrow().el.is_static {
+            node_ptr.borrow_mut().el.is_static = false;
+        }
+    }
+}
+
+// returns the number of static roots found in the subtree
+fn mark_static_roots(tree: &ASTTree, node_ptr: &Rc<RefCell<ASTNode>>, is_in_for: bool) -> usize {
+    let mut node = node_ptr.borrow_mut();
+    if node.el.kind != ASTElementKind::Element {
+        return 0;
+    }
+
+    if node.el.is_static || node.el.once {
+        node.el.static_in_for = is_in_for;
+    }
+
+    // For a node to qualify as a static root, it should have children that
+    // are not just static text. Otherwise the cost of hoisting out will
+    // outweigh the benefits and it's better off to just always render it fresh.
+    let only_static_text =
+        node.children.len() == 1 && node.children[0].borrow().el.kind == ASTElementKind::Text;
+    if node.el.is_static && !node.children.is_empty() && !only_static_text {
+        node.el.static_root = true;
+        return 1;
+    }
+    node.el.static_root = false;
+
+    let children_in_for = is_in_for || node.el.for_value.is_some();
+    let children: Vec<Rc<RefCell<ASTNode>>> = node.children.to_vec();
+    let blocks = if_condition_blocks(tree, &node);
+    drop(node);
+
+    children
+        .iter()
+        .map(|child| mark_static_roots(tree, child, children_in_for))
+        .chain(
+            blocks
+                .iter()
+                .map(|block| mark_static_roots(tree, block, is_in_for)),
+        )
+        .sum()
+}
+
+// the v-else-if and v-else branches, the first condition block is the node itself
+fn if_condition_blocks(tree: &ASTTree, node: &ASTNode) -> Vec<Rc<RefCell<ASTNode>>> {
+    node.el
+        .if_conditions
+        .iter()
+        .flatten()
+        .filter(|condition| condition.block_id != node.id)
+        .filter_map(|condition| tree.get(condition.block_id).cloned())
+        .collect()
+}
+
+fn is_static(node: &ASTNode) -> bool {
+    let el = &node.el;
+
+    match el.kind {
+        ASTElementKind::Expression => false,
+        ASTElementKind::Text => true,
+        ASTElementKind::Element | ASTElementKind::Root => {
+            el.pre
+                || (!el.has_bindings
+                    && el.if_val.is_none()
+                    && el.else_if_val.is_none()
+                    && !el.is_else
+                    && el.for_value.is_none()
+                    // not a built-in
+                    && !el.token.data.eq_ignore_ascii_case("slot")
+                    && !el.token.data.eq_ignore_ascii_case("component")
+                    // not a component
+                    && is_reserved_tag(&el.token.data)
+                    && !is_direct_child_of_template_for(node)
+                    && has_only_static_data(node))
+        }
+    }
+}
+
+// nothing beyond the tag, plain attributes, static class and static style ends up in the vnode data
+fn has_only_static_data(node: &ASTNode) -> bool {
+    let el = &node.el;
+
+    el.key.is_none()
+        && el.ref_val.is_none()
+        && !el.once
+        && el.component.is_none()
+        && !el.inline_template
+        && el.slot_target.is_none()
+        && el.slot_scope.is_none()
+        && el.scoped_slots.is_none()
+        && el.props.is_empty()
+        && el.dynamic_attrs.is_empty()
+        && el.directives.is_none()
+        && el.v_bind_object.is_none()
+        && el.v_on_object.is_none()
+        && el.events.is_none()
+        && el.native_events.is_none()
+        && el.class_binding.is_none()
+        && el.style_binding.is_none()
+}
+
+fn is_direct_child_of_template_for(node: &ASTNode) -> bool {
+    let mut parent = node.parent.as_ref().and_then(|parent| parent.upgrade());
+
+    while let Some(parent_ptr) = parent {
+        let parent_node = parent_ptr.borrow();
+        if !parent_node.el.token.data.eq_ignore_ascii_case("template") {
+            return false;
+        }
+        if parent_node.el.for_value.is_some() {
+            return true;
+        }
+        parent = parent_node
+            .parent
+            .as_ref()
+            .and_then(|parent| parent.upgrade());
+    }
+
+    false
+}
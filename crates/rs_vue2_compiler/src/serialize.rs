@@ -0,0 +1,187 @@
+use crate::ast_tree::{ASTElementKind, ASTNode, ASTTree, IfCondition};
+use rs_html_parser_tokens::Token;
+use serde::ser::{Error, Serialize, SerializeMap, Serializer};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
+use unicase_collections::unicase_btree_set::UniCaseBTreeSet;
+
+impl ASTTree {
+    /// Serializes the tree to a compact JSON string, useful for debugging and snapshot tests.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+impl ASTNode {
+    /// Serializes the node and its descendants to a compact JSON string.
+    ///
+    /// The node has no access to the tree, so the other branches of its if conditions are
+    /// only referenced by their `block_id`. Serialize the [`ASTTree`] to have them inline.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+// the tree is serialized starting from the wrapper node, children, if condition blocks and
+// scoped slots are nested inline and parents are referenced by id to avoid the cycle
+impl Serialize for ASTTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        NodeSerializer {
+            node: &self.wrapper.borrow(),
+            tree: Some(self),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Serialize for ASTNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        NodeSerializer {
+            node: self,
+            tree: None,
+        }
+        .serialize(serializer)
+    }
+}
+
+struct NodeSerializer<'a> {
+    node: &'a ASTNode,
+    // the if condition blocks are looked up from the tree
+    tree: Option<&'a ASTTree>,
+}
+
+struct NodeRefSerializer<'a> {
+    node: &'a Rc<RefCell<ASTNode>>,
+    tree: Option<&'a ASTTree>,
+}
+
+#[derive(serde::Serialize)]
+struct IfConditionSerializer<'a> {
+    #[serde(flatten)]
+    condition: &'a IfCondition,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block: Option<NodeRefSerializer<'a>>,
+}
+
+impl Serialize for NodeRefSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        NodeSerializer {
+            node: &self.node.borrow(),
+            tree: self.tree,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Serialize for NodeSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let node = self.node;
+        let el = &node.el;
+        let mut map = serializer.serialize_map(None)?;
+
+        map.serialize_entry("id", &node.id)?;
+        if node.parent.is_some() {
+            map.serialize_entry("parent", &node.parent_id)?;
+        }
+        if el.kind == ASTElementKind::Element {
+            map.serialize_entry("component", &node.is_maybe_component())?;
+        }
+
+        // the element fields left at their defaults are omitted to keep the output readable
+        if let Value::Object(fields) = compact(serde_json::to_value(el).map_err(S::Error::custom)?)
+        {
+            for (name, value) in &fields {
+                map.serialize_entry(name, value)?;
+            }
+        }
+
+        if let Some(if_conditions) = &el.if_conditions {
+            let conditions: Vec<_> = if_conditions
+                .iter()
+                .map(|condition| IfConditionSerializer {
+                    condition,
+                    // the first condition is the node itself
+                    block: self
+                        .tree
+                        .filter(|_| condition.block_id != node.id)
+                        .and_then(|tree| tree.get(condition.block_id))
+                        .map(|block| NodeRefSerializer {
+                            node: block,
+                            tree: self.tree,
+                        }),
+                })
+                .collect();
+            map.serialize_entry("if_conditions", &conditions)?;
+        }
+        if let Some(scoped_slots) = &el.scoped_slots {
+            let slots: BTreeMap<&str, _> = scoped_slots
+                .iter()
+                .map(|(name, slot)| {
+                    let slot = NodeRefSerializer {
+                        node: slot,
+                        tree: self.tree,
+                    };
+                    (name.as_ref(), slot)
+                })
+                .collect();
+            map.serialize_entry("scoped_slots", &slots)?;
+        }
+
+        let children: Vec<_> = node
+            .children
+            .iter()
+            .map(|child| NodeRefSerializer {
+                node: child,
+                tree: self.tree,
+            })
+            .collect();
+        map.serialize_entry("children", &children)?;
+
+        map.end()
+    }
+}
+
+// drops nulls, false flags and empty collections
+fn compact(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name, compact(value)))
+                .filter(|(_, value)| match value {
+                    Value::Null | Value::Bool(false) => false,
+                    Value::Array(items) => !items.is_empty(),
+                    Value::Object(fields) => !fields.is_empty(),
+                    _ => true,
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(compact).collect()),
+        value => value,
+    }
+}
+
+pub(crate) fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+pub(crate) fn token_data<S: Serializer>(token: &Token, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&token.data)
+}
+
+pub(crate) fn unicase_set<S: Serializer>(
+    set: &UniCaseBTreeSet,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(set.iter().map(|key| key.as_ref()))
+}
+
+pub(crate) fn unicase_map<S: Serializer, V: Serialize>(
+    map: &UniCaseBTreeMap<V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().map(|(key, value)| (key.as_ref(), value)))
+}
@@ -0,0 +1,185 @@
+use rs_html_parser_tokenizer::{Tokenizer, TokenizerOptions};
+use rs_html_parser_tokenizer_tokens::TokenizerTokenLocation;
+use std::collections::HashMap;
+
+use crate::ast_tree::SourceRange;
+use crate::util::decode_custom_entities;
+
+// The parser tokens drop some details of the source, attributes are stored in a map which loses
+// the order they were written in and CDATA sections are emitted as comments.
+// This scans the template once more with the tokenizer to recover that information.
+#[derive(Debug, Default)]
+pub struct SourceScan {
+    // one entry for every open tag the parser emits as a non-implied OpenTag token
+    pub open_tags: Vec<OpenTagSource>,
+    // one entry for every Comment token the parser emits, true when it was a CDATA section
+    pub cdata_comments: Vec<bool>,
+    // every close tag written in the template, the parser drops the ones without an open element
+    pub close_tags: Vec<CloseTagSource>,
+    // one entry for every Text token the parser emits, entities are tokens of their own
+    pub texts: Vec<TextSource>,
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct TextSource {
+    // byte offsets of the text as written
+    pub start: usize,
+    pub end: usize,
+    // the token is an entity the tokenizer decoded, its text is not the source
+    pub entity: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct CloseTagSource {
+    pub name: String,
+    // byte offsets of the close tag, from `<` up to and including `>`
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct OpenTagSource {
+    // attribute names in source order, duplicates are only listed once
+    pub attr_names: Vec<String>,
+    // byte offsets of attribute values without the quotes, keyed by the attribute name
+    pub attr_value_ranges: Vec<(String, usize, usize)>,
+    // attribute values with the supplementary entities decoded, only with a custom entity table.
+    // The parser only sees the values after the tokenizer decoded the standard entities
+    pub attr_values: Vec<(String, String)>,
+    // byte offsets of the start tag, from `<` up to and including `>`
+    pub start: usize,
+    pub end: usize,
+}
+
+pub fn scan_source(
+    template: &str,
+    options: &TokenizerOptions,
+    entities: Option<&HashMap<String, String>>,
+) -> SourceScan {
+    let mut scan = SourceScan::default();
+    // the value of a repeated attribute is dropped by the parser, so its range is skipped too
+    let mut in_first_attr = false;
+
+    for tokenizer_token in Tokenizer::new(template.as_bytes(), options) {
+        let text = template
+            .get(tokenizer_token.start..tokenizer_token.end)
+            .unwrap_or_default();
+
+        match tokenizer_token.location {
+            TokenizerTokenLocation::OpenTagName => scan.open_tags.push(OpenTagSource {
+                start: tokenizer_token.start.saturating_sub(1),
+                ..Default::default()
+            }),
+            // the parser opens a <br> for a stray </br>
+            TokenizerTokenLocation::CloseTag if text == "br" => {
+                scan.open_tags.push(OpenTagSource {
+                    start: tokenizer_token.start.saturating_sub(2),
+                    end: tokenizer_token.end + 1,
+                    ..Default::default()
+                })
+            }
+            TokenizerTokenLocation::CloseTag => scan.close_tags.push(CloseTagSource {
+                name: text.to_string(),
+                start: tokenizer_token.start.saturating_sub(2),
+                end: tokenizer_token.end + 1,
+            }),
+            TokenizerTokenLocation::OpenTagEnd | TokenizerTokenLocation::SelfClosingTag => {
+                if let Some(open_tag) = scan.open_tags.last_mut() {
+                    open_tag.end = tokenizer_token.start + 1;
+                }
+            }
+            TokenizerTokenLocation::AttrName => {
+                if let Some(open_tag) = scan.open_tags.last_mut() {
+                    in_first_attr = !open_tag.attr_names.iter().any(|name| name == text);
+                    if in_first_attr {
+                        open_tag.attr_names.push(text.to_string());
+                        if entities.is_some() {
+                            open_tag.attr_values.push((text.to_string(), String::new()));
+                        }
+                    }
+                }
+            }
+            // values containing entities are split into several data tokens
+            TokenizerTokenLocation::AttrData if in_first_attr => {
+                if let Some(open_tag) = scan.open_tags.last_mut() {
+                    if let (Some(entities), Some((_name, value))) =
+                        (entities, open_tag.attr_values.last_mut())
+                    {
+                        value.push_str(&decode_custom_entities(text, entities));
+                    }
+                    let name = open_tag.attr_names.last().cloned().unwrap_or_default();
+                    match open_tag.attr_value_ranges.last_mut() {
+                        Some((last_name, _start, end)) if *last_name == name => {
+                            *end = tokenizer_token.end;
+                        }
+                        _ => open_tag.attr_value_ranges.push((
+                            name,
+                            tokenizer_token.start,
+                            tokenizer_token.end,
+                        )),
+                    }
+                }
+            }
+            TokenizerTokenLocation::AttrEntity if in_first_attr => {
+                if let Some((_name, value)) = scan
+                    .open_tags
+                    .last_mut()
+                    .and_then(|open_tag| open_tag.attr_values.last_mut())
+                {
+                    value.extend(char::from_u32(tokenizer_token.code));
+                }
+            }
+            TokenizerTokenLocation::Text | TokenizerTokenLocation::TextEntity => {
+                scan.texts.push(TextSource {
+                    start: tokenizer_token.start,
+                    end: tokenizer_token.end,
+                    entity: tokenizer_token.location == TokenizerTokenLocation::TextEntity,
+                })
+            }
+            TokenizerTokenLocation::Comment => scan.cdata_comments.push(false),
+            TokenizerTokenLocation::CData => scan.cdata_comments.push(true),
+            _ => {}
+        }
+    }
+
+    scan
+}
+
+// the byte offsets where each line of the template starts, built once to turn offsets
+// into line and column positions
+#[derive(Debug)]
+pub struct LineIndex<'a> {
+    template: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(template: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(template.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        LineIndex {
+            template,
+            line_starts,
+        }
+    }
+
+    pub fn range(&self, start: usize, end: usize) -> SourceRange {
+        let line = self
+            .line_starts
+            .partition_point(|&line_start| line_start <= start);
+        let line_start = self.line_starts[line - 1];
+        let column = self
+            .template
+            .get(line_start..start)
+            .map_or(start - line_start, |text| text.chars().count());
+
+        SourceRange {
+            start,
+            end,
+            line,
+            column: column + 1,
+        }
+    }
+}
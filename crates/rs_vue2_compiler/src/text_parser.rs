@@ -1,7 +1,7 @@
+use crate::codegen::to_js_string;
 use crate::filter_parser::parse_filters;
 use lazy_static::lazy_static;
 use regex::Regex;
-use rs_html_parser_tokenizer_tokens::QuoteType;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -30,10 +30,12 @@ fn build_regex(delimiters: &(String, String)) -> Arc<Regex> {
     regex
 }
 
+// returns the render expression, the raw tokens and the names of the filters used
 pub fn parse_text(
     text: &str,
     delimiters: &Option<(String, String)>,
-) -> Option<(String, Vec<String>)> {
+    to_string_helper: &str,
+) -> Option<(String, Vec<String>, Vec<String>)> {
     let tag_re = match delimiters {
         Some(delimiters) => build_regex(delimiters),
         None => Arc::clone(&DEFAULT_REGEX),
@@ -43,26 +45,25 @@ pub fn parse_text(
     }
     let mut tokens = Vec::new();
     let mut raw_tokens = Vec::new();
+    let mut filters = Vec::new();
     let mut last_index = 0;
     for cap in tag_re.captures_iter(text) {
         let index = cap.get(0).unwrap().start();
         if index > last_index {
             let token_value = text[last_index..index].to_string();
             raw_tokens.push(token_value.clone());
-            tokens.push(format!(r#""{}""#, token_value));
+            tokens.push(to_js_string(&token_value));
         }
-        let exp = parse_filters(&(
-            cap[1].trim().to_string().into_boxed_str(),
-            QuoteType::NoValue,
-        ));
-        tokens.push(format!("_s({})", exp));
+        let (exp, exp_filters) = parse_filters(cap[1].trim());
+        filters.extend(exp_filters);
+        tokens.push(format!("{}({})", to_string_helper, exp));
         raw_tokens.push(format!("@binding: {}", exp));
         last_index = cap.get(0).unwrap().end();
     }
     if last_index < text.len() {
         let token_value = text[last_index..].to_string();
         raw_tokens.push(token_value.clone());
-        tokens.push(format!(r#""{}""#, token_value));
+        tokens.push(to_js_string(&token_value));
     }
-    Some((tokens.join("+"), raw_tokens))
+    Some((tokens.join("+"), raw_tokens, filters))
 }
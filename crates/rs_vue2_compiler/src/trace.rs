@@ -0,0 +1,33 @@
+// Compilation phase spans, only active with the `tracing` feature.
+// Every phase is a `tracing` span at trace level under the "rs_vue2_compiler" target,
+// named after the phase, so any tracing subscriber can time or filter them.
+
+pub const TRACE_TARGET: &str = "rs_vue2_compiler";
+
+// exits the span when dropped
+#[cfg(feature = "tracing")]
+pub struct PhaseSpan {
+    pub(crate) _entered: tracing::span::EnteredSpan,
+}
+
+#[cfg(not(feature = "tracing"))]
+pub struct PhaseSpan;
+
+// enters the span of a compilation phase, span names have to be known at compile time
+#[cfg(feature = "tracing")]
+macro_rules! phase_span {
+    ($name:literal) => {
+        $crate::trace::PhaseSpan {
+            _entered: tracing::trace_span!(target: $crate::trace::TRACE_TARGET, $name).entered(),
+        }
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! phase_span {
+    ($name:literal) => {
+        $crate::trace::PhaseSpan
+    };
+}
+
+pub(crate) use phase_span;
@@ -1,7 +1,8 @@
-use crate::MODIFIER_RE;
+use crate::{MODIFIER_RE, NAMED_ENTITY_RE};
 use rs_html_parser_tokenizer_tokens::QuoteType;
 use rs_html_parser_tokens::Token;
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::HashMap;
 
 pub fn has_attribute(token: &Token, str: &str) -> bool {
     if let Some(attrs) = &token.attrs {
@@ -26,40 +27,99 @@ pub fn get_attribute_value(token: &Token, str: &str) -> Option<Option<(Box<str>,
     None
 }
 
+// the expression of `:name` or `v-bind:name`, the value of a bound attribute
+pub fn get_bound_attribute_value(
+    token: &Token,
+    name: &str,
+) -> Option<Option<(Box<str>, QuoteType)>> {
+    get_attribute_value(token, &format!(":{}", name))
+        .or_else(|| get_attribute_value(token, &format!("v-bind:{}", name)))
+}
+
 pub fn prepend_modifier_marker(symbol: char, name: &str, dynamic: bool) -> String {
     return if dynamic {
-        format!("_p({}, \"{}\")", name, symbol)
+        format!("_p({},\"{}\")", name, symbol)
     } else {
         format!("{}{}", symbol, name)
     };
 }
 
+// matches of "\.[^.\]]+(?=[^\]]*$)", the look-ahead keeps dots inside a dynamic argument
+// such as v-bind:[a.b] from being read as modifiers
+pub fn modifier_matches(input: &str) -> impl Iterator<Item = regex::Match<'_>> {
+    MODIFIER_RE
+        .find_iter(input)
+        .filter(|matched| !input[matched.end()..].contains(']'))
+}
+
 pub fn modifier_regex_replace_all_matches(input: &str) -> String {
-    // This method simulates the original regex behavior
-    // "\.[^.\]]+(?=[^\]]*$)"
-    let mut replaced_string = input.to_string();
-
-    for captures in MODIFIER_RE.captures_iter(input) {
-        let matched_string = &captures[0];
-        if !matched_string.contains(']') {
-            replaced_string = replaced_string.replace(matched_string, "");
-        }
+    let mut replaced_string = String::with_capacity(input.len());
+    let mut last_end = 0;
+
+    for matched in modifier_matches(input) {
+        replaced_string += &input[last_end..matched.start()];
+        last_end = matched.end();
     }
+    replaced_string += &input[last_end..];
 
     replaced_string
 }
 
-pub fn parse_style_text(css_text: &str) -> BTreeMap<String, String> {
-    let mut res = BTreeMap::new();
+// style declarations in written order, a repeated property keeps its first position
+pub fn parse_style_text(css_text: &str) -> Vec<(String, String)> {
+    let mut res: Vec<(String, String)> = Vec::new();
 
-    for item in css_text.split(";") {
-        if !item.is_empty() {
-            let tmp: Vec<&str> = item.split(":").collect();
-            if tmp.len() > 1 {
-                res.insert(tmp[0].trim().to_string(), tmp[1].trim().to_string());
+    // split on semicolons outside of parentheses, url(data:...;base64,...) stays in one piece
+    let mut items = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, char) in css_text.char_indices() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                items.push(&css_text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&css_text[start..]);
+
+    for item in items {
+        // only the first colon separates the property, values such as urls may contain more
+        if let Some((property, value)) = item.split_once(':') {
+            if value.is_empty() {
+                continue;
+            }
+            let property = property.trim().to_string();
+            let value = value.trim().to_string();
+
+            match res.iter_mut().find(|(existing, _)| *existing == property) {
+                Some(existing) => existing.1 = value,
+                None => res.push((property, value)),
             }
         }
     }
 
     res
 }
+
+// The tokenizer already decodes the standard entities, the ones it does not know are left as
+// written. Those are looked up in the supplementary table. The text has to be the source as
+// written, an escaped `&amp;custom;` is decoded by the tokenizer and must stay literal.
+pub fn decode_custom_entities<'t>(
+    source: &'t str,
+    entities: &HashMap<String, String>,
+) -> Cow<'t, str> {
+    if !source.contains('&') {
+        return Cow::Borrowed(source);
+    }
+
+    NAMED_ENTITY_RE.replace_all(source, |captures: &regex::Captures| {
+        match entities.get(&captures[1]) {
+            Some(value) => value.to_string(),
+            None => captures[0].to_string(),
+        }
+    })
+}
@@ -1,5 +1,13 @@
+use crate::ast_tree::SourceRange;
+use std::cell::RefCell;
+use std::rc::Rc;
+
 pub trait WarnLogger {
     fn call(&mut self, msg: &str);
+    // loggers that only take the message ignore the range
+    fn call_with_range(&mut self, msg: &str, _range: Option<SourceRange>) {
+        self.call(msg)
+    }
     fn clone_box(&self) -> Box<dyn WarnLogger>;
 }
 
@@ -33,3 +41,34 @@ impl Clone for Box<dyn WarnLogger> {
         self.clone_box()
     }
 }
+
+// a handler that also receives the source range of the warning, when it is known.
+// Ranges are only recorded with CompilerOptions::output_source_range
+#[derive(Clone)]
+pub struct RangeWarnLogger<F>(pub F);
+
+impl<F> WarnLogger for RangeWarnLogger<F>
+where
+    F: FnMut(&str, Option<SourceRange>) + Clone + 'static,
+{
+    fn call(&mut self, msg: &str) {
+        (self.0)(msg, None)
+    }
+
+    fn call_with_range(&mut self, msg: &str, range: Option<SourceRange>) {
+        (self.0)(msg, range)
+    }
+
+    fn clone_box(&self) -> Box<dyn WarnLogger> {
+        Box::new(self.clone())
+    }
+}
+
+// used when CompilerOptions::warn is not set, the warnings end up in the result instead
+pub(crate) fn collecting_warn_logger() -> (Box<dyn WarnLogger>, Rc<RefCell<Vec<String>>>) {
+    let warnings = Rc::new(RefCell::new(Vec::new()));
+    let warnings_clone = Rc::clone(&warnings);
+    let warn = move |msg: &str| warnings_clone.borrow_mut().push(msg.to_string());
+
+    (Box::new(warn), warnings)
+}
@@ -1,4 +1,35 @@
+use lazy_static::lazy_static;
 use rs_html_parser_tokenizer_tokens::QuoteType;
+use std::collections::HashMap;
+
+lazy_static! {
+    // svg attributes with mixed case names, keyed by the lowercase name.
+    // Same table as the attribute adjustment of the html parsing spec
+    static ref SVG_CAMEL_CASE_ATTRS: HashMap<String, &'static str> = {
+        let words = "attributeName,attributeType,baseFrequency,baseProfile,calcMode,\
+            clipPathUnits,diffuseConstant,edgeMode,filterUnits,glyphRef,gradientTransform,\
+            gradientUnits,kernelMatrix,kernelUnitLength,keyPoints,keySplines,keyTimes,\
+            lengthAdjust,limitingConeAngle,markerHeight,markerUnits,markerWidth,\
+            maskContentUnits,maskUnits,numOctaves,pathLength,patternContentUnits,\
+            patternTransform,patternUnits,pointsAtX,pointsAtY,pointsAtZ,preserveAlpha,\
+            preserveAspectRatio,primitiveUnits,refX,refY,repeatCount,repeatDur,\
+            requiredExtensions,requiredFeatures,specularConstant,specularExponent,\
+            spreadMethod,startOffset,stdDeviation,stitchTiles,surfaceScale,systemLanguage,\
+            tableValues,targetX,targetY,textLength,viewBox,viewTarget,xChannelSelector,\
+            yChannelSelector,zoomAndPan";
+        words
+            .split(',')
+            .map(|word| (word.to_ascii_lowercase(), word))
+            .collect()
+    };
+}
+
+// the attribute name as svg expects it, e.g. viewbox -> viewBox
+pub fn svg_attr_name(name: &str) -> Option<&'static str> {
+    SVG_CAMEL_CASE_ATTRS
+        .get(&name.to_ascii_lowercase())
+        .copied()
+}
 
 fn accept_value(tag: &str) -> bool {
     matches!(tag, "input" | "textarea" | "option" | "select" | "progress")
@@ -13,7 +44,7 @@ pub fn must_use_prop(
         && accept_value(tag)
         && type_attribute
             .as_ref()
-            .is_some_and(|x1| x1.as_ref().is_some_and(|x2| x2.0.as_ref() != "button"))
+            .is_none_or(|x1| x1.as_ref().is_none_or(|x2| x2.0.as_ref() != "button"))
     {
         return true;
     }
@@ -1,4 +1,5 @@
 use crate::ast_tree::{ASTNode, ASTTree};
+use crate::codegen::to_js_string;
 use crate::text_parser::parse_text;
 use crate::{CompilerOptions, ModuleApi};
 use std::cell::RefCell;
@@ -14,16 +15,25 @@ impl ModuleApi for ClassModule {
 
         if let Some(static_class) = &static_class {
             if let Some(static_class_val) = &static_class.value {
-                node.el.static_class = Some(static_class_val.replace(" ", "").to_string());
+                node.el.static_class = Some(
+                    static_class_val
+                        .split_whitespace()
+                        .collect::<Vec<&str>>()
+                        .join(" "),
+                );
 
                 if node.is_dev {
-                    let parsed = parse_text(static_class_val, &options.delimiters);
+                    let parsed = parse_text(
+                        static_class_val,
+                        &options.delimiters,
+                        &options.helpers.to_string,
+                    );
 
                     if parsed.is_some() {
-                        node.warn.call(&format!(
+                        node.warn_with_range(&format!(
                             "class=\"{}\": Interpolation inside attributes has been removed. \
                 Use v-bind or the colon shorthand instead. For example, \
-                instead of <div class=\"{{ val }}\">, use <div :class=\"val\">.",
+                instead of <div class=\"{{{{ val }}}}\">, use <div :class=\"val\">.",
                             static_class_val
                         ));
                     }
@@ -31,7 +41,7 @@ impl ModuleApi for ClassModule {
             }
         }
 
-        let class_binding = node.get_binding_attr("class", true);
+        let class_binding = node.get_binding_attr("class", false);
 
         if !class_binding.is_empty() {
             node.el.class_binding = Some(class_binding);
@@ -42,7 +52,7 @@ impl ModuleApi for ClassModule {
         let mut data = String::new();
 
         if let Some(static_class) = &node.el.static_class {
-            data += &format!("staticClass:{},", static_class);
+            data += &format!("staticClass:{},", to_js_string(static_class));
         }
 
         if let Some(class_binding) = &node.el.class_binding {
@@ -8,21 +8,21 @@ use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
 
 pub struct ModelModule {}
 
-fn node_copy(node: &ASTNode, tree: &ASTTree) -> Rc<RefCell<ASTNode>> {
-    tree.create(
-        create_ast_element(
-            Token {
-                data: node.el.token.data.clone(),
-                attrs: node.el.token.attrs.clone(),
-                kind: node.el.token.kind,
-                is_implied: node.el.token.is_implied,
-            },
-            ASTElementKind::Element,
-        ),
-        node.parent_id,
-        node.is_dev,
-        node.warn.clone_box(),
-    )
+fn node_copy(node: &ASTNode, tree: &mut ASTTree) -> Rc<RefCell<ASTNode>> {
+    let mut el = create_ast_element(
+        Token {
+            data: node.el.token.data.clone(),
+            attrs: node.el.token.attrs.clone(),
+            kind: node.el.token.kind,
+            is_implied: node.el.token.is_implied,
+        },
+        ASTElementKind::Element,
+    );
+    el.attr_names = node.el.attr_names.clone();
+    // attributes consumed from the original element are not processed again on the copies
+    el.ignored = node.el.ignored.clone();
+
+    tree.create(el, node.parent_id, node.is_dev, node.warn.clone_box())
 }
 
 impl ModuleApi for ModelModule {
@@ -43,28 +43,37 @@ impl ModuleApi for ModelModule {
         options: &CompilerOptions,
     ) -> Option<Rc<RefCell<ASTNode>>> {
         if node.el.token.data.as_ref() == "input" {
-            if let Some(map) = &node.el.token.attrs {
+            if let Some(map) = &mut node.el.token.attrs {
                 if !map.contains_key("v-model") {
                     return None;
                 }
 
-                let mut type_binding = None;
-                if map.contains_key(":type") || map.contains_key("v-bind:type") {
-                    // type_binding = Some(node.get_binding_attr("type", true));
+                // the input type is matched case insensitively by the browser, a static type is
+                // lowercased so it can be compared when picking the model implementation
+                if let Some(Some((static_type, _))) = map.get_mut("type") {
+                    if static_type.chars().any(|c| c.is_ascii_uppercase()) {
+                        *static_type = static_type.to_ascii_lowercase().into_boxed_str();
+                    }
                 }
-                if type_binding.is_none() && !map.contains_key("type") && map.contains_key("v-bind")
-                {
-                    let v_bind_attr = map.get("v-bind");
 
-                    if let Some(v_bind_attr) = v_bind_attr {
-                        if let Some((v_bind_attr, _)) = v_bind_attr {
-                            type_binding = Some(format!("({}).type", v_bind_attr));
-                        }
+                let has_dynamic_type = map.contains_key(":type") || map.contains_key("v-bind:type");
+                let v_bind_object = match map.get("v-bind") {
+                    Some(Some((v_bind_attr, _))) if !map.contains_key("type") => {
+                        Some(v_bind_attr.to_string())
                     }
-                }
+                    _ => None,
+                };
+
+                // a bound type is only known at runtime, the element is expanded into a
+                // checkbox, a radio and a generic branch picked by the type value
+                let type_binding = if has_dynamic_type {
+                    Some(node.get_binding_attr("type", true))
+                } else {
+                    v_bind_object.map(|v_bind_attr| format!("({}).type", v_bind_attr))
+                };
 
                 if let Some(type_binding) = type_binding {
-                    let if_condition = node.get_and_remove_attr("v-if", true);
+                    let if_condition = node.get_and_remove_attr("v-if", false);
                     let if_condition_val = if let Some(if_cond) = if_condition {
                         if_cond.value
                     } else {
@@ -75,14 +84,13 @@ impl ModuleApi for ModelModule {
                     } else {
                         String::new()
                     };
-                    let has_else = node.get_and_remove_attr("v-else", true).is_some();
-                    let else_if_condition = node.get_and_remove_attr("v-else-if", true);
+                    let has_else = node.get_and_remove_attr("v-else", false).is_some();
+                    let else_if_condition = node.get_and_remove_attr("v-else-if", false);
 
                     // 1. checkbox
                     let branch0_rc = node_copy(node, tree);
                     {
                         let mut branch0 = branch0_rc.borrow_mut();
-                        tree.set(branch0.id, branch0_rc.clone());
                         branch0.process_for();
                         branch0
                             .el
@@ -108,8 +116,7 @@ impl ModuleApi for ModelModule {
                         // 2. add radio else-if condition
                         let branch1_rc = node_copy(node, tree);
                         let mut branch1 = branch1_rc.borrow_mut();
-                        tree.set(branch1.id, branch1_rc.clone());
-                        branch1.get_and_remove_attr("v-for", true);
+                        branch1.get_and_remove_attr("v-for", false);
                         branch1
                             .el
                             .token
@@ -131,8 +138,7 @@ impl ModuleApi for ModelModule {
                         // 3. other
                         let branch2_rc = node_copy(node, tree);
                         let mut branch2 = branch2_rc.borrow_mut();
-                        tree.set(branch2.id, branch2_rc.clone());
-                        branch2.get_and_remove_attr("v-for", true);
+                        branch2.get_and_remove_attr("v-for", false);
                         branch2
                             .el
                             .token
@@ -145,6 +151,7 @@ impl ModuleApi for ModelModule {
                                     QuoteType::Single,
                                 )),
                             );
+                        branch2.el.ignored.remove(":type");
                         branch2.process_element(tree, options);
                         branch0.add_if_condition(IfCondition {
                             exp: if_condition_val.clone(),
@@ -157,7 +164,7 @@ impl ModuleApi for ModelModule {
                             if let Some(else_if_val) = else_if_condition.value {
                                 branch0.el.else_if_val = Some(else_if_val);
                             } else {
-                                node.warn.call("empty v-else-if condition");
+                                node.warn_with_range("empty v-else-if condition");
                             }
                         }
                     }
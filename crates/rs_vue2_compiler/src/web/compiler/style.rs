@@ -1,4 +1,5 @@
 use crate::ast_tree::{ASTNode, ASTTree};
+use crate::codegen::to_js_string;
 use crate::text_parser::parse_text;
 use crate::util::parse_style_text;
 use crate::{CompilerOptions, ModuleApi};
@@ -14,26 +15,27 @@ impl ModuleApi for StyleModule {
         if let Some(static_style_attr) = &static_style_attr_opt {
             if let Some(static_style) = &static_style_attr.value {
                 if node.is_dev {
-                    let res = parse_text(static_style, &options.delimiters);
+                    let res = parse_text(
+                        static_style,
+                        &options.delimiters,
+                        &options.helpers.to_string,
+                    );
                     if res.is_some() {
-                        node.warn.call(&format!(
+                        node.warn_with_range(&format!(
                             "style=\"{}\": Interpolation inside attributes has been removed. \
                         Use v-bind or the colon shorthand instead. For example, \
-                        instead of <div style=\"{{ val }}\">, use <div :style=\"val\">.",
+                        instead of <div style=\"{{{{ val }}}}\">, use <div :style=\"val\">.",
                             static_style
                         ));
                     }
                 }
-                let result = serde_json::to_string(&parse_style_text(static_style));
-                match result {
-                    Ok(result) => {
-                        node.el.static_style = Some(result);
-                    }
-                    Err(err) => {
-                        node.el.static_style = None;
-                        node.warn.call(&format!("Failed to parse style: {}", err));
-                    }
-                }
+                let declarations: Vec<String> = parse_style_text(static_style)
+                    .iter()
+                    .map(|(property, value)| {
+                        format!("{}:{}", to_js_string(property), to_js_string(value))
+                    })
+                    .collect();
+                node.el.static_style = Some(format!("{{{}}}", declarations.join(",")));
             }
         }
 
@@ -2,6 +2,45 @@ use lazy_static::lazy_static;
 use unicase_collections::unicase_btree_set::UniCaseBTreeSet;
 
 lazy_static! {
+    static ref HTML_TAGS: UniCaseBTreeSet = {
+        let mut set = UniCaseBTreeSet::new();
+        let words = "html,body,base,head,link,meta,style,title,\
+            address,article,aside,footer,header,h1,h2,h3,h4,h5,h6,hgroup,nav,section,\
+            div,dd,dl,dt,figcaption,figure,picture,hr,img,li,main,ol,p,pre,ul,\
+            a,b,abbr,bdi,bdo,br,cite,code,data,dfn,em,i,kbd,mark,q,rp,rt,rtc,ruby,\
+            s,samp,small,span,strong,sub,sup,time,u,var,wbr,area,audio,map,track,video,\
+            embed,object,param,source,canvas,script,noscript,del,ins,\
+            caption,col,colgroup,table,thead,tbody,td,th,tr,\
+            button,datalist,fieldset,form,input,label,legend,meter,optgroup,option,\
+            output,progress,select,textarea,\
+            details,dialog,menu,menuitem,summary,\
+            content,element,shadow,template,blockquote,iframe,tfoot";
+        for word in words.split(',') {
+            set.insert(word.to_string());
+        }
+        set
+    };
+    // elements that never have content, they are closed as soon as they are opened
+    static ref VOID_TAGS: UniCaseBTreeSet = {
+        let mut set = UniCaseBTreeSet::new();
+        let words = "area,base,br,col,embed,hr,img,input,link,meta,param,source,track,wbr";
+        for word in words.split(',') {
+            set.insert(word.to_string());
+        }
+        set
+    };
+    // elements rendered as blocks by default, whitespace next to them is not rendered
+    static ref BLOCK_TAGS: UniCaseBTreeSet = {
+        let mut set = UniCaseBTreeSet::new();
+        let words = "address,article,aside,blockquote,body,caption,colgroup,dd,details,dialog,\
+            div,dl,dt,fieldset,figcaption,figure,footer,form,h1,h2,h3,h4,h5,h6,head,header,\
+            hgroup,hr,html,legend,li,main,menu,nav,ol,optgroup,option,p,pre,section,summary,\
+            table,tbody,td,tfoot,th,thead,tr,ul";
+        for word in words.split(',') {
+            set.insert(word.to_string());
+        }
+        set
+    };
     static ref SVG_TAGS: UniCaseBTreeSet = {
         let mut set = UniCaseBTreeSet::new();
         let words = "svg,animate,circle,clippath,cursor,defs,desc,ellipse,filter,font-face,\
@@ -14,11 +53,27 @@ lazy_static! {
     };
 }
 
+pub fn is_html_tag(tag: &str) -> bool {
+    HTML_TAGS.contains(tag)
+}
+
 pub fn is_svg_tag(tag: &str) -> bool {
     SVG_TAGS.contains(tag)
 }
 
-pub fn get_namespace(tag: &str) -> Option<&'static str> {
+pub fn is_unary_tag(tag: &str) -> bool {
+    VOID_TAGS.contains(tag)
+}
+
+pub fn is_block_tag(tag: &str) -> bool {
+    BLOCK_TAGS.contains(tag)
+}
+
+pub fn is_reserved_tag(tag: &str) -> bool {
+    is_html_tag(tag) || is_svg_tag(tag)
+}
+
+pub fn get_tag_namespace(tag: &str) -> Option<&'static str> {
     if is_svg_tag(tag) {
         Some("svg")
     } else if tag.eq_ignore_ascii_case("math") {
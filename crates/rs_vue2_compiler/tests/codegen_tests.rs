@@ -0,0 +1,649 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::codegen::generate;
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::web::compiler::model::ModelModule;
+    use rs_vue2_compiler::web::compiler::style::StyleModule;
+    use rs_vue2_compiler::{compile, CompilerOptions, VueParser};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn default_options() -> CompilerOptions {
+        CompilerOptions {
+            dev: true,
+            new_slot_syntax: true,
+            modules: Some(vec![
+                Box::new(ClassModule {}),
+                Box::new(ModelModule {}),
+                Box::new(StyleModule {}),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn assert_codegen(template: &str, expected_render: &str, options: &CompilerOptions) {
+        let mut parser = VueParser::new(options);
+        let ast = parser.parse(template);
+        let result = generate(&ast, options);
+
+        assert_eq!(result.render, expected_render);
+    }
+
+    #[test]
+    fn generate_element_with_children() {
+        assert_codegen(
+            "<div><p>hello</p><span>{{ msg }} world</span></div>",
+            "with(this){return _c('div',[_c('p',[_v(\"hello\")]),_c('span',[_v(_s(msg)+\" world\")])])}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_empty_template() {
+        assert_codegen("", "with(this){return _c(\"div\")}", &default_options());
+    }
+
+    #[test]
+    fn generate_comment() {
+        let options = CompilerOptions {
+            preserve_comments: true,
+            ..default_options()
+        };
+        assert_codegen(
+            "<div><!--keep--></div>",
+            "with(this){return _c('div',[_e(\"keep\")])}",
+            &options,
+        );
+
+        // the text is escaped into a string literal
+        assert_codegen(
+            "<div><!-- \"a\" \\ b\n\u{2028}c --><p>x</p></div>",
+            "with(this){return _c('div',[_e(\" \\\"a\\\" \\\\ b\\n\\u2028c \"),_c('p',[_v(\"x\")])])}",
+            &options,
+        );
+
+        // comments are dropped by default
+        assert_codegen(
+            "<div><!--keep--></div>",
+            "with(this){return _c('div')}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_merged_text() {
+        assert_codegen(
+            "<div>a {{ b }} c</div>",
+            "with(this){return _c('div',[_v(\"a \"+_s(b)+\" c\")])}",
+            &default_options(),
+        );
+        // a dropped comment does not split the text
+        assert_codegen(
+            "<div>a<!--c-->{{ b }}</div>",
+            "with(this){return _c('div',[_v(\"a\"+_s(b))])}",
+            &default_options(),
+        );
+        // whitespace between elements is condensed into a single space node,
+        // it is dropped when it contains a line break
+        assert_codegen(
+            "<div><b>{{ x }}</b> <i>y</i>\n  <i>z</i></div>",
+            "with(this){return _c('div',[_c('b',[_v(_s(x))]),_v(\" \"),_c('i',[_v(\"y\")]),_c('i',[_v(\"z\")])])}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_svg_attribute_case() {
+        assert_codegen(
+            "<svg viewBox=\"0 0 10 10\"><path :d=\"d\"/></svg>",
+            "with(this){return _c('svg',{attrs:{\"viewBox\":\"0 0 10 10\"}},[_c('path',{attrs:{\"d\":d}})])}",
+            &default_options(),
+        );
+        // lowercased names are restored inside svg only
+        assert_codegen(
+            "<div viewbox=\"a\"><svg viewbox=\"0 0 10 10\" :preserveaspectratio=\"p\"><lineargradient gradientunits=\"u\"/></svg></div>",
+            "with(this){return _c('div',{attrs:{\"viewbox\":\"a\"}},[_c('svg',{attrs:{\"viewBox\":\"0 0 10 10\",\"preserveAspectRatio\":p}},[_c('lineargradient',{attrs:{\"gradientUnits\":\"u\"}})],1)])}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_v_html_and_v_text() {
+        let result = compile(
+            "<div><p v-html=\"html\"></p><p v-text=\"msg\"></p></div>",
+            default_options(),
+        );
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',[_c('p',{domProps:{\"innerHTML\":_s(html)}}),_c('p',{domProps:{\"textContent\":_s(msg)}})])}"
+        );
+        assert!(result.errors.is_empty());
+
+        let result = compile(
+            "<div><p v-html=\"html\"><b>x</b></p><span v-text=\"msg\">text {{ a }}</span></div>",
+            default_options(),
+        );
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',[_c('p',{domProps:{\"innerHTML\":_s(html)}}),_c('span',{domProps:{\"textContent\":_s(msg)}})])}"
+        );
+        assert_eq!(
+            result.errors,
+            vec![
+                "v-html=\"html\": v-html replaces the content of <p>, its children will be ignored.",
+                "v-text=\"msg\": v-text replaces the content of <span>, its children will be ignored.",
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_resolved_component() {
+        fn resolve_component(tag: &str) -> Option<String> {
+            match tag {
+                "ui-button" => Some("UiButton".to_string()),
+                _ => None,
+            }
+        }
+
+        let options = CompilerOptions {
+            resolve_component: Some(resolve_component),
+            ..default_options()
+        };
+        assert_codegen(
+            "<div><ui-button>ok</ui-button><ui-input></ui-input><span></span></div>",
+            "with(this){return _c('div',[_c('UiButton',[_v(\"ok\")]),_c('ui-input'),_c('span')],1)}",
+            &options,
+        );
+    }
+
+    #[test]
+    fn generate_dynamic_component() {
+        assert_codegen(
+            "<div><component :is=\"view\"></component><component is=\"foo\">x</component></div>",
+            "with(this){return _c('div',[_c(view,{tag:\"component\"}),_c(\"foo\",{tag:\"component\"},[_v(\"x\")])],1)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_style_data() {
+        assert_codegen(
+            "<div style=\"color:red\" :style=\"s\"></div>",
+            "with(this){return _c('div',{staticStyle:{\"color\":\"red\"},style:(s)})}",
+            &default_options(),
+        );
+
+        // declarations keep their written order, semicolons and colons inside values are kept
+        assert_codegen(
+            "<div style=\"width: 1px; background: url(data:image/png;base64,AA) ; color:\"></div>",
+            "with(this){return _c('div',{staticStyle:{\"width\":\"1px\",\"background\":\"url(data:image/png;base64,AA)\"}})}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_data_key_ref_and_class() {
+        assert_codegen(
+            "<transition><p key=\"a\" ref=\"r\" class=\" a  b \" :class=\"c\"></p></transition>",
+            "with(this){return _c('transition',[_c('p',{key:\"a\",ref:\"r\",staticClass:\"a b\",class:c})])}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_data_attrs_and_props() {
+        assert_codegen(
+            "<input id=\"x\" :value=\"v\" disabled :title=\"t\">",
+            "with(this){return _c('input',{attrs:{\"id\":\"x\",\"disabled\":\"\",\"title\":t},domProps:{\"value\":v}})}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_data_events() {
+        assert_codegen(
+            "<button @click=\"go\" @focus=\"go($event)\" @keyup=\"count++\" @[evt]=\"go\"></button>",
+            "with(this){return _c('button',{on:_d({\"click\":go,\"focus\":function($event){return go($event)},\"keyup\":function($event){count++}},[evt,go])})}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_dynamic_event_with_modifiers() {
+        assert_codegen(
+            "<div @[evt].once=\"go\"></div>",
+            "with(this){return _c('div',{on:_d({},[_p(evt,\"~\"),go])})}",
+            &default_options(),
+        );
+        // the event may be a key event, .right is kept for the arrow key filter
+        assert_codegen(
+            "<div @[evt].right=\"go\"></div>",
+            "with(this){return _c('div',{on:_d({},[(evt)==='click'?'contextmenu':(evt),function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"right\",39,$event.key,[\"Right\",\"ArrowRight\"]))return null;if('button' in $event && $event.button !== 2)return null;return go.apply(null, arguments)}])})}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_v_model_input_type() {
+        // a static type is read as written, lowercased
+        assert_codegen(
+            "<div><input type=\"CheckBox\" v-model=\"c\"></div>",
+            "with(this){return _c('div',[_c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(c),expression:\"c\"}],attrs:{\"type\":\"checkbox\"}})])}",
+            &default_options(),
+        );
+
+        // a bound type is resolved at runtime
+        assert_codegen(
+            "<div><input :type=\"t\" v-model=\"v\"></div>",
+            concat!(
+                "with(this){return _c('div',[((t)==='checkbox')?",
+                "_c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(v),expression:\"v\"}],attrs:{\"type\":\"checkbox\"}})",
+                ":((t)==='radio')?",
+                "_c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(v),expression:\"v\"}],attrs:{\"type\":\"radio\"}})",
+                ":_c('input',{directives:[{name:\"model\",rawName:\"v-model\",value:(v),expression:\"v\"}],attrs:{\"type\":t}})])}"
+            ),
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_data_directives() {
+        assert_codegen(
+            "<div v-focus:arg.a=\"ok\" v-text=\"msg\" v-cloak></div>",
+            "with(this){return _c('div',{directives:[{name:\"focus\",rawName:\"v-focus:arg.a\",value:(ok),expression:\"ok\",arg:\"arg\",modifiers:{\"a\":true}}],domProps:{\"textContent\":_s(msg)}})}",
+            &default_options(),
+        );
+
+        // an empty value generates neither the value nor the expression
+        assert_codegen(
+            "<div v-foo=\"\" v-show=\" \"></div>",
+            "with(this){return _c('div',{directives:[{name:\"foo\",rawName:\"v-foo\"},{name:\"show\",rawName:\"v-show\"}]})}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<input v-model=\"\">",
+            "with(this){return _c('input',{directives:[{name:\"model\",rawName:\"v-model\"}]})}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_data_slot_target() {
+        assert_codegen(
+            "<comp><p slot=\"one\">x</p></comp>",
+            "with(this){return _c('comp',[_c('p',{attrs:{\"slot\":\"one\"},slot:\"one\"},[_v(\"x\")])])}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_data_dynamic_attrs_and_v_bind_object() {
+        assert_codegen(
+            "<div :[k]=\"v\" v-bind.prop=\"o\"></div>",
+            "with(this){return _c('div',_b(_b({},\"div\",_d({},[k,v])),'div',o,true))}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_scoped_slots() {
+        assert_codegen(
+            "<comp><template #foo=\"s\">{{ s.a }}</template></comp>",
+            "with(this){return _c('comp',{scopedSlots:_u([{key:\"foo\",fn:function(s){return [_v(_s(s.a))]}}])})}",
+            &default_options(),
+        );
+
+        // dynamic slot names force the scoped slots to be updated with the parent
+        assert_codegen(
+            "<comp><template #[name]>x</template></comp>",
+            "with(this){return _c('comp',{scopedSlots:_u([{key:name,fn:function(){return [_v(\"x\")]},proxy:true}],null,true)})}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_if_and_for() {
+        assert_codegen(
+            "<div><p v-if=\"a\">a</p><p v-else>b</p><span v-for=\"(item, i) in items\">{{ i }}</span></div>",
+            "with(this){return _c('div',[(a)?_c('p',[_v(\"a\")]):_c('p',[_v(\"b\")]),_l((items),function(item,i){return _c('span',[_v(_s(i))])})],2)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_slot_outlet() {
+        assert_codegen(
+            "<div><slot name=\"foo\" :item-value=\"v\">fallback</slot><slot v-bind=\"o\"></slot></div>",
+            "with(this){return _c('div',[_t(\"foo\",[_v(\"fallback\")],{\"itemValue\":v}),_t(\"default\",null,null,o)],2)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_slot_outlet_in_v_for() {
+        assert_codegen(
+            "<div><slot v-for=\"i in l\" :name=\"i\"/></div>",
+            "with(this){return _c('div',[_l((l),function(i){return _t(i)})],2)}",
+            &default_options(),
+        );
+
+        // the fallback content is rendered for every iteration with its own scope
+        assert_codegen(
+            "<div><slot v-for=\"i in l\" :name=\"i\">fallback {{ i }}</slot></div>",
+            "with(this){return _c('div',[_l((l),function(i){return _t(i,[_v(\"fallback \"+_s(i))])})],2)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_v_for_destructuring() {
+        assert_codegen(
+            "<ul><li v-for=\"{ id, name } in items\" :key=\"id\">{{ name }}</li></ul>",
+            "with(this){return _c('ul',_l((items),function({ id, name }){return _c('li',{key:id},[_v(_s(name))])}),0)}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<ul><li v-for=\"([first, second], i) in pairs\">{{ first }}</li></ul>",
+            "with(this){return _c('ul',_l((pairs),function([first, second],i){return _c('li',[_v(_s(first))])}),0)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_inline_template() {
+        assert_codegen(
+            "<my-comp inline-template><div>{{ a }}</div></my-comp>",
+            "with(this){return _c('my-comp',{inlineTemplate:{render:function(){with(this){return _c('div',[_v(_s(a))])}},staticRenderFns:[]}})}",
+            &default_options(),
+        );
+
+        // static roots of the inline template are hoisted into its own render functions
+        let result = compile(
+            "<div><p><b>x</b></p><my-comp inline-template><div :id=\"a\"><p><b>y</b></p></div></my-comp></div>",
+            default_options(),
+        );
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',[_m(0),_c('my-comp',{inlineTemplate:{render:function(){with(this){return _c('div',{attrs:{\"id\":a}},[_m(0)])}},staticRenderFns:[function(){with(this){return _c('p',[_c('b',[_v(\"y\")])])}}]}})],1)}"
+        );
+        assert_eq!(
+            result.static_render_fns,
+            vec!["with(this){return _c('p',[_c('b',[_v(\"x\")])])}"]
+        );
+
+        let warnings = Rc::new(RefCell::new(vec![]));
+        let warnings_clone = warnings.clone();
+        let options = CompilerOptions {
+            dev: true,
+            warn: Some(Box::new(move |msg: &str| {
+                warnings_clone.borrow_mut().push(msg.to_string());
+            })),
+            ..default_options()
+        };
+        let result = compile("<my-comp inline-template><p></p><p></p></my-comp>", options);
+        assert_eq!(
+            result.render,
+            "with(this){return _c('my-comp',{inlineTemplate:{render:function(){with(this){return _c('p')}},staticRenderFns:[]}})}"
+        );
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["Inline-template components must have exactly one child element."]
+        );
+    }
+
+    #[test]
+    fn generate_children_normalization() {
+        // plain elements and text need no normalization
+        assert_codegen(
+            "<div><p>a</p>b</div>",
+            "with(this){return _c('div',[_c('p',[_v(\"a\")]),_v(\"b\")])}",
+            &default_options(),
+        );
+        // a component may render several root nodes
+        assert_codegen(
+            "<div><p></p><my-comp></my-comp></div>",
+            "with(this){return _c('div',[_c('p'),_c('my-comp')],1)}",
+            &default_options(),
+        );
+        // v-for, <template> and <slot> can nest arrays
+        assert_codegen(
+            "<div><p></p><p v-for=\"i in n\"></p></div>",
+            "with(this){return _c('div',[_c('p'),_l((n),function(i){return _c('p')})],2)}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<div><template v-if=\"a\"><p></p></template></div>",
+            "with(this){return _c('div',[(a)?[_c('p')]:_e()],2)}",
+            &default_options(),
+        );
+        // a single v-for child is the children array itself
+        assert_codegen(
+            "<div><p v-for=\"i in n\"></p></div>",
+            "with(this){return _c('div',_l((n),function(i){return _c('p')}),0)}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<div><my-comp v-for=\"i in n\"></my-comp></div>",
+            "with(this){return _c('div',_l((n),function(i){return _c('my-comp')}),1)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_trimmed_block_whitespace() {
+        let template =
+            "<div> <p>a</p> <p>b <b>c</b> <i>d</i></p> <span>e</span> <span>f</span> </div>";
+
+        // condense keeps a single space between the blocks
+        assert_codegen(
+            template,
+            "with(this){return _c('div',[_c('p',[_v(\"a\")]),_v(\" \"),_c('p',[_v(\"b \"),_c('b',[_v(\"c\")]),_v(\" \"),_c('i',[_v(\"d\")])]),_v(\" \"),_c('span',[_v(\"e\")]),_v(\" \"),_c('span',[_v(\"f\")])])}",
+            &default_options(),
+        );
+
+        let options = CompilerOptions {
+            trim_block_whitespace: true,
+            ..default_options()
+        };
+        assert_codegen(
+            template,
+            "with(this){return _c('div',[_c('p',[_v(\"a\")]),_c('p',[_v(\"b \"),_c('b',[_v(\"c\")]),_v(\" \"),_c('i',[_v(\"d\")])]),_c('span',[_v(\"e\")]),_v(\" \"),_c('span',[_v(\"f\")])])}",
+            &options,
+        );
+
+        // whitespace inside <pre> is kept as written
+        assert_codegen(
+            "<pre> <p>a</p> </pre>",
+            "with(this){return _c('pre',[_v(\" \"),_c('p',[_v(\"a\")]),_v(\" \")])}",
+            &options,
+        );
+    }
+
+    #[test]
+    fn generate_multiple_roots() {
+        let options = CompilerOptions {
+            allow_multiple_roots: true,
+            ..default_options()
+        };
+        assert_codegen(
+            "<div>a</div><div :id=\"b\"></div>",
+            "with(this){return [_c('div',[_v(\"a\")]),_c('div',{attrs:{\"id\":b}})]}",
+            &options,
+        );
+
+        // a single root is not wrapped
+        assert_codegen(
+            "<div>a</div>",
+            "with(this){return _c('div',[_v(\"a\")])}",
+            &options,
+        );
+    }
+
+    #[test]
+    fn generate_event_handlers() {
+        assert_codegen(
+            "<button @click=\"onClick\" @focus=\"count++\" @blur=\"reset()\"></button>",
+            "with(this){return _c('button',{on:{\"blur\":function($event){return reset()},\"click\":onClick,\"focus\":function($event){count++}}})}",
+            &default_options(),
+        );
+        // .native listens on the root element of a component
+        assert_codegen(
+            "<my-comp @click.native=\"onClick\" @click=\"onEmit\"></my-comp>",
+            "with(this){return _c('my-comp',{on:{\"click\":onEmit},nativeOn:{\"click\":onClick}})}",
+            &default_options(),
+        );
+        // key modifiers filter keyboard events before the other modifiers run
+        assert_codegen(
+            "<input @keyup.enter.prevent=\"submit\" @keydown.13=\"a\" @keypress.right=\"b\">",
+            "with(this){return _c('input',{on:{\"keydown\":function($event){if(!$event.type.indexOf('key')&&$event.keyCode!==13)return null;return a.apply(null, arguments)},\"keypress\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"right\",39,$event.key,[\"Right\",\"ArrowRight\"]))return null;if('button' in $event && $event.button !== 2)return null;return b.apply(null, arguments)},\"keyup\":function($event){if(!$event.type.indexOf('key')&&_k($event.keyCode,\"enter\",13,$event.key,\"Enter\"))return null;$event.preventDefault();return submit.apply(null, arguments)}}})}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<div @click.self.stop=\"x = 1\" @mousedown.ctrl.exact=\"(e) => go(e)\"></div>",
+            "with(this){return _c('div',{on:{\"click\":function($event){if($event.target !== $event.currentTarget)return null;$event.stopPropagation();x = 1},\"mousedown\":function($event){if(!$event.ctrlKey)return null;if($event.shiftKey||$event.altKey||$event.metaKey)return null;return ((e) => go(e)).apply(null, arguments)}}})}",
+            &default_options(),
+        );
+        // .middle listens on mouseup and keeps the button guard
+        assert_codegen(
+            "<div @click.middle=\"f\"></div>",
+            "with(this){return _c('div',{on:{\"mouseup\":function($event){if('button' in $event && $event.button !== 1)return null;return f.apply(null, arguments)}}})}",
+            &default_options(),
+        );
+        // modifiers run in the order they were written
+        assert_codegen(
+            "<div><a @click.self.prevent=\"go\"></a><a @click.prevent.self=\"go\"></a></div>",
+            "with(this){return _c('div',[_c('a',{on:{\"click\":function($event){if($event.target !== $event.currentTarget)return null;$event.preventDefault();return go.apply(null, arguments)}}}),_c('a',{on:{\"click\":function($event){$event.preventDefault();if($event.target !== $event.currentTarget)return null;return go.apply(null, arguments)}}})])}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_v_on_object() {
+        assert_codegen(
+            "<button v-on=\"listeners\"></button>",
+            "with(this){return _c('button',_g({},listeners))}",
+            &default_options(),
+        );
+        // the listeners are merged last, after v-bind="object"
+        assert_codegen(
+            "<button v-on=\"listeners\" @click=\"f\" v-bind=\"attrs\"></button>",
+            "with(this){return _c('button',_g(_b({on:{\"click\":f}},'button',attrs,false),listeners))}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_ref() {
+        assert_codegen(
+            "<div><p ref=\"title\"></p></div>",
+            "with(this){return _c('div',[_c('p',{ref:\"title\"})])}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<ul><li v-for=\"i in n\" ref=\"items\"></li></ul>",
+            "with(this){return _c('ul',_l((n),function(i){return _c('li',{ref:\"items\",refInFor:true})}),0)}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<ul><li v-for=\"i in n\"><b :ref=\"i\"></b></li></ul>",
+            "with(this){return _c('ul',_l((n),function(i){return _c('li',[_c('b',{ref:i,refInFor:true})])}),0)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn generate_v_once() {
+        assert_codegen(
+            "<div><p>{{ a }}</p></div>",
+            "with(this){return _c('div',[_c('p',[_v(_s(a))])])}",
+            &default_options(),
+        );
+        assert_codegen(
+            "<div><p v-once>{{ a }}</p></div>",
+            "with(this){return _c('div',[_m(0)])}",
+            &default_options(),
+        );
+
+        // only the rendered branch of a condition is cached
+        assert_codegen(
+            "<div><p v-if=\"ok\" v-once>a</p></div>",
+            "with(this){return _c('div',[(ok)?_m(0):_e()])}",
+            &default_options(),
+        );
+
+        // inside v-for the cache is keyed by the v-for key
+        assert_codegen(
+            "<div><div v-for=\"i in list\" :key=\"i\"><p v-once>{{ i }}</p></div></div>",
+            "with(this){return _c('div',_l((list),function(i){return _c('div',{key:i},[_o(_c('p',[_v(_s(i))]),0,i)])}),0)}",
+            &default_options(),
+        );
+    }
+
+    #[test]
+    fn v_once_in_unkeyed_v_for_warns() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let warnings_clone = Rc::clone(&warnings);
+        let options = CompilerOptions {
+            warn: Some(Box::new(move |msg: &str| {
+                warnings_clone.borrow_mut().push(msg.to_string())
+            })),
+            ..default_options()
+        };
+        assert_codegen(
+            "<div><div v-for=\"i in list\"><p v-once>{{ i }}</p></div></div>",
+            "with(this){return _c('div',_l((list),function(i){return _c('div',[_c('p',[_v(_s(i))])])}),0)}",
+            &options,
+        );
+        assert!(warnings
+            .borrow()
+            .contains(&"v-once can only be used inside v-for that is keyed.".to_string()));
+    }
+
+    #[test]
+    fn generate_static_render_fns() {
+        let result = compile(
+            "<div><ul><li>a</li><li>b</li></ul>{{ msg }}<p v-once>{{ msg }}</p></div>",
+            default_options(),
+        );
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',[_m(0),_v(_s(msg)),_m(1)])}"
+        );
+        assert_eq!(
+            result.static_render_fns,
+            vec![
+                "with(this){return _c('ul',[_c('li',[_v(\"a\")]),_c('li',[_v(\"b\")])])}",
+                "with(this){return _c('p',[_v(_s(msg))])}",
+            ]
+        );
+
+        // static roots inside v-for are marked so the runtime can key them per iteration
+        let result = compile(
+            "<div><div v-for=\"i in list\" :key=\"i\"><ul><li>a</li></ul></div></div>",
+            default_options(),
+        );
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',_l((list),function(i){return _c('div',{key:i},[_m(0,true)])}),0)}"
+        );
+        assert_eq!(result.static_render_fns.len(), 1);
+    }
+
+    #[test]
+    fn generate_static_only_template() {
+        let result = compile(
+            "<div class=\"a\"><h1>title</h1><p>text <b>bold</b></p></div>",
+            default_options(),
+        );
+        assert_eq!(result.render, "with(this){return _m(0)}");
+        assert_eq!(
+            result.static_render_fns,
+            vec!["with(this){return _c('div',{staticClass:\"a\"},[_c('h1',[_v(\"title\")]),_c('p',[_v(\"text \"),_c('b',[_v(\"bold\")])])])}"]
+        );
+
+        // a single static text child is cheaper to render inline than to hoist
+        let result = compile("<div>text</div>", default_options());
+        assert_eq!(result.render, "with(this){return _c('div',[_v(\"text\")])}");
+        assert!(result.static_render_fns.is_empty());
+    }
+}
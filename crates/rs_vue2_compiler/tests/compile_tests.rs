@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::ast_tree::SourceRange;
+    use rs_vue2_compiler::codegen::generate;
+    use rs_vue2_compiler::web::compiler::class::ClassModule;
+    use rs_vue2_compiler::web::compiler::model::ModelModule;
+    use rs_vue2_compiler::web::compiler::style::StyleModule;
+    use rs_vue2_compiler::{
+        compile, CompilerOptions, CompilerOptionsError, RangeWarnLogger, RenderHelpers, VueParser,
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn default_options() -> CompilerOptions {
+        CompilerOptions {
+            dev: true,
+            new_slot_syntax: true,
+            modules: Some(vec![
+                Box::new(ClassModule {}),
+                Box::new(ModelModule {}),
+                Box::new(StyleModule {}),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn compile_template() {
+        let result = compile(
+            "<div id=\"app\" :class=\"{ active: ok }\"><p v-if=\"ok\">{{ msg }}</p><p v-else>none</p></div>",
+            default_options(),
+        );
+
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',{class:{ active: ok },attrs:{\"id\":\"app\"}},[(ok)?_c('p',[_v(_s(msg))]):_c('p',[_v(\"none\")])])}"
+        );
+        assert!(result.static_render_fns.is_empty());
+        assert!(result.errors.is_empty());
+
+        let wrapper = result.ast.wrapper.borrow();
+        assert_eq!(wrapper.children[0].borrow().el.token.data, Box::from("div"));
+    }
+
+    #[test]
+    fn compile_list_with_events() {
+        let result = compile(
+            "<ul><li v-for=\"(item, index) in items\" :key=\"item.id\" @click=\"select(index)\">{{ item.name }}</li></ul>",
+            default_options(),
+        );
+
+        assert_eq!(
+            result.render,
+            "with(this){return _c('ul',_l((items),function(item,index){return _c('li',{key:item.id,on:{\"click\":function($event){return select(index)}}},[_v(_s(item.name))])}),0)}"
+        );
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn compile_collects_errors() {
+        let result = compile("<div></div><p></p>", default_options());
+
+        assert_eq!(result.render, "with(this){return _c('div')}");
+        assert_eq!(
+            result.errors,
+            vec!["Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead."]
+        );
+    }
+
+    #[test]
+    fn warnings_without_handler_are_collected() {
+        let options = CompilerOptions {
+            dev: true,
+            ..Default::default()
+        };
+        let ast = VueParser::new(&options).parse("<div></div><p></p>");
+        assert_eq!(
+            ast.warnings,
+            vec!["Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead."]
+        );
+
+        let ast = VueParser::new(&options)
+            .parse("<div><p v-for=\"i in l\"><b v-once>{{ i }}</b></p></div>");
+        assert!(ast.warnings.is_empty());
+        assert_eq!(
+            generate(&ast, &options).warnings,
+            vec!["v-once can only be used inside v-for that is keyed."]
+        );
+    }
+
+    #[test]
+    fn warn_handler_with_range() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let warnings_clone = Rc::clone(&warnings);
+        let options = CompilerOptions {
+            dev: true,
+            output_source_range: true,
+            warn: Some(Box::new(RangeWarnLogger(
+                move |msg: &str, range: Option<SourceRange>| {
+                    warnings_clone.borrow_mut().push((msg.to_string(), range))
+                },
+            ))),
+            ..Default::default()
+        };
+
+        let result = compile("<div>\n  <span v-model=\"x\"></span></div><p></p>", options);
+        // the handler receives the warnings, nothing is left to collect
+        assert!(result.errors.is_empty());
+        assert!(result.ast.warnings.is_empty());
+        assert_eq!(
+            *warnings.borrow(),
+            vec![
+                (
+                    "<span v-model=\"x\">: v-model is not supported on this element type. If you are working with contenteditable, it's recommended to wrap a library dedicated for that purpose inside a custom component.".to_string(),
+                    Some(SourceRange {
+                        start: 8,
+                        end: 33,
+                        line: 2,
+                        column: 3
+                    })
+                ),
+                (
+                    "Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.".to_string(),
+                    Some(SourceRange {
+                        start: 39,
+                        end: 46,
+                        line: 2,
+                        column: 34
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn parser_from_default_options() {
+        let options = CompilerOptions::default();
+        assert!(!options.dev);
+        assert!(!options.is_ssr);
+
+        let ast = VueParser::new(&options).parse("<div><p>{{ msg }}</p></div>");
+        let wrapper = ast.wrapper.borrow();
+        assert_eq!(wrapper.children[0].borrow().el.token.data, Box::from("div"));
+    }
+
+    #[test]
+    fn byte_order_mark_and_line_separators() {
+        // the leading BOM is not text outside the root element
+        let result = compile("\u{feff}<div>a</div>", default_options());
+        assert_eq!(result.render, "with(this){return _c('div',[_v(\"a\")])}");
+        assert!(result.errors.is_empty());
+
+        // line and paragraph separators are not whitespace and are escaped in string literals
+        let result = compile(
+            "<div title=\"a\u{2028}b\">c\u{2028}d</div>",
+            default_options(),
+        );
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',{attrs:{\"title\":\"a\\u2028b\"}},[_v(\"c\\u2028d\")])}"
+        );
+        let result = compile("<div>say \"{{ a }}\"\u{2029}</div>", default_options());
+        assert_eq!(
+            result.render,
+            "with(this){return _c('div',[_v(\"say \\\"\"+_s(a)+\"\\\"\\u2029\")])}"
+        );
+    }
+
+    #[test]
+    fn custom_render_helpers() {
+        let options = CompilerOptions::builder()
+            .helpers(RenderHelpers {
+                create_element: "h".to_string(),
+                create_text: "createTextVNode".to_string(),
+                create_empty: "createEmptyVNode".to_string(),
+                to_string: "toDisplayString".to_string(),
+                render_list: "renderList".to_string(),
+                ..RenderHelpers::default()
+            })
+            .build()
+            .unwrap();
+        let result = compile(
+            "<ul><li v-for=\"item in items\" v-text=\"item.label\"></li><li v-if=\"!items.length\">{{ empty }} items</li></ul>",
+            options,
+        );
+
+        assert_eq!(
+            result.render,
+            "with(this){return h('ul',[renderList((items),function(item){return h('li',{domProps:{\"textContent\":toDisplayString(item.label)}})}),(!items.length)?h('li',[createTextVNode(toDisplayString(empty)+\" items\")]):createEmptyVNode()],2)}"
+        );
+
+        // vue 2 names by default
+        let result = compile("<p v-if=\"ok\">{{ a }}</p>", CompilerOptions::default());
+        assert_eq!(
+            result.render,
+            "with(this){return (ok)?_c('p',[_v(_s(a))]):_e()}"
+        );
+    }
+
+    #[test]
+    fn options_builder() {
+        let options = CompilerOptions::builder()
+            .dev(true)
+            .new_slot_syntax(true)
+            .web_modules()
+            .build()
+            .unwrap();
+        assert!(options.dev);
+        assert_eq!(
+            options.modules.as_ref().map(|modules| modules.len()),
+            Some(3)
+        );
+
+        // the same output as the options spelled out field by field
+        let template = "<div :class=\"{ a: ok }\" style=\"color: red\"><input :type=\"t\" v-model=\"x\"></div>";
+        assert_eq!(
+            compile(template, options).render,
+            compile(template, default_options()).render
+        );
+
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let warnings_clone = Rc::clone(&warnings);
+        let options = CompilerOptions::builder()
+            .dev(true)
+            .warn(move |msg: &str| warnings_clone.borrow_mut().push(msg.to_string()))
+            .build()
+            .unwrap();
+        VueParser::new(&options).parse("<div v-model=\"x\"></div>");
+        assert_eq!(warnings.borrow().len(), 1);
+
+        assert_eq!(
+            CompilerOptions::builder()
+                .delimiters("%", "%")
+                .build()
+                .err(),
+            Some(CompilerOptionsError::IdenticalDelimiters("%".to_string()))
+        );
+    }
+}
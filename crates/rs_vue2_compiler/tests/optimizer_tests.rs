@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::ast_tree::ASTTree;
+    use rs_vue2_compiler::optimizer::optimize;
+    use rs_vue2_compiler::{compile, CompilerOptions, VueParser};
+
+    fn parse_and_optimize(template: &str) -> ASTTree {
+        let options = CompilerOptions::default();
+        let mut ast = VueParser::new(&options).parse(template);
+        optimize(&mut ast);
+
+        ast
+    }
+
+    #[test]
+    fn static_root_count() {
+        let ast = parse_and_optimize(
+            "<div><ul><li>a</li><li>b</li></ul><p>{{ msg }}</p><section><h1>title</h1></section><span>text</span></div>",
+        );
+        assert_eq!(ast.stats.static_roots, 2);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert!(!root.el.is_static);
+        let flags: Vec<(bool, bool)> = root
+            .children
+            .iter()
+            .map(|child| (child.borrow().el.is_static, child.borrow().el.static_root))
+            .collect();
+        // a static element with only a text child is not worth hoisting
+        assert_eq!(
+            flags,
+            vec![(true, true), (false, false), (true, true), (true, false)]
+        );
+    }
+
+    #[test]
+    fn bindings_and_components_are_not_static() {
+        let ast = parse_and_optimize(
+            "<div><p :id=\"id\"><b>a</b></p><p v-if=\"ok\"><b>a</b></p><my-comp><b>a</b></my-comp><p key=\"k\"><b>a</b></p></div>",
+        );
+        assert_eq!(ast.stats.static_roots, 0);
+    }
+
+    #[test]
+    fn static_in_for() {
+        let ast = parse_and_optimize("<div><p v-for=\"i in list\"><b><i>a</i></b></p></div>");
+        assert_eq!(ast.stats.static_roots, 1);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let p = root.children[0].borrow();
+        let b = p.children[0].borrow();
+        assert!(b.el.static_root);
+        assert!(b.el.static_in_for);
+    }
+
+    #[test]
+    fn compile_reports_static_roots() {
+        let result = compile(
+            "<div><ul><li>a</li></ul>{{ msg }}</div>",
+            CompilerOptions::default(),
+        );
+        assert_eq!(result.ast.stats.static_roots, 1);
+    }
+
+    #[test]
+    fn multiple_roots() {
+        let options = CompilerOptions {
+            allow_multiple_roots: true,
+            ..Default::default()
+        };
+        let template = "<ul><li>a</li></ul><p>{{ msg }}</p><section><h1>title</h1></section>";
+        let mut ast = VueParser::new(&options).parse(template);
+        optimize(&mut ast);
+        assert_eq!(ast.stats.static_roots, 2);
+
+        let wrapper = ast.wrapper.borrow();
+        let flags: Vec<(bool, bool)> = wrapper
+            .children
+            .iter()
+            .map(|root| (root.borrow().el.is_static, root.borrow().el.static_root))
+            .collect();
+        assert_eq!(flags, vec![(true, true), (false, false), (true, true)]);
+        drop(wrapper);
+
+        let result = compile(template, options);
+        assert_eq!(
+            result.render,
+            "with(this){return [_m(0),_c('p',[_v(_s(msg))]),_m(1)]}"
+        );
+        assert_eq!(result.static_render_fns.len(), 2);
+    }
+}
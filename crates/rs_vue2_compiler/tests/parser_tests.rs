@@ -1,16 +1,21 @@
 #[cfg(test)]
 mod tests {
     use rs_html_parser_tokenizer_tokens::QuoteType;
-    use rs_html_parser_tokens::TokenKind;
-    use rs_vue2_compiler::ast_tree::{ASTElementKind, ASTTree};
+    use rs_html_parser_tokens::{Token, TokenKind};
+    use rs_vue2_compiler::ast_tree::{create_ast_element, pattern_identifiers, ASTNode};
+    use rs_vue2_compiler::ast_tree::{ASTElementKind, ASTTree, NormalizationType, SourceRange};
     use rs_vue2_compiler::web::compiler::class::ClassModule;
     use rs_vue2_compiler::web::compiler::model::ModelModule;
     use rs_vue2_compiler::web::compiler::style::StyleModule;
-    use rs_vue2_compiler::{CompilerOptions, VueParser, WhitespaceHandling};
+    use rs_vue2_compiler::{
+        CompilerOptions, CompilerOptionsError, NodeTransform, PreNodeTransform, RangeWarnLogger,
+        RenderHelpers, VueParser, WhitespaceHandling,
+    };
     use std::cell::RefCell;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use std::rc::Rc;
     use unicase::UniCase;
+    use unicase_collections::unicase_btree_map::UniCaseBTreeMap;
 
     fn parse(template: &str) -> (ASTTree, Rc<RefCell<Vec<String>>>) {
         let warnings = Rc::new(RefCell::new(Vec::new()));
@@ -21,18 +26,31 @@ mod tests {
             v_bind_prop_short_hand: false,
             preserve_comments: false,
             whitespace_handling: WhitespaceHandling::Condense,
+            trim_block_whitespace: false,
             new_slot_syntax: true,
+            auto_key_v_for: false,
+            compat_number_modifier: false,
+            output_source_range: false,
+            allow_multiple_roots: false,
+            component_name: None,
             is_pre_tag: None,
-            get_namespace: None,
+            is_unary_tag: None,
+            get_tag_namespace: None,
+            resolve_component: None,
             warn: Some(Box::new(move |msg: &str| {
                 warnings_clone.borrow_mut().push(msg.to_string());
             })),
             delimiters: None,
+            entities: None,
+            helpers: RenderHelpers::default(),
             modules: Some(vec![
                 Box::new(ClassModule {}),
                 Box::new(ModelModule {}),
                 Box::new(StyleModule {}),
             ]),
+            pre_transforms: None,
+            transforms: None,
+            post_transforms: None,
         };
         let mut parser = VueParser::new(&options);
 
@@ -45,6 +63,34 @@ mod tests {
         parser.parse(template)
     }
 
+    #[test]
+    fn invalid_delimiters_option() {
+        let options = CompilerOptions {
+            delimiters: Some(("%%".to_string(), "%%".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            VueParser::try_new(&options).err(),
+            Some(CompilerOptionsError::IdenticalDelimiters("%%".to_string()))
+        );
+
+        let options = CompilerOptions {
+            delimiters: Some(("${".to_string(), "}".to_string())),
+            ..Default::default()
+        };
+        assert!(VueParser::try_new(&options).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "open and close delimiters cannot both be")]
+    fn invalid_delimiters_option_panics_in_new() {
+        let options = CompilerOptions {
+            delimiters: Some(("||".to_string(), "||".to_string())),
+            ..Default::default()
+        };
+        VueParser::new(&options);
+    }
+
     #[test]
     fn simple_element() {
         let (ast, _warnings) = parse("<h1>hello world</h1>");
@@ -73,6 +119,761 @@ mod tests {
         );
     }
 
+    fn interpolation_expression(template: &str) -> String {
+        let (ast, _warnings) = parse(template);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let expression = root.children[0].borrow().el.expression.clone().unwrap();
+
+        expression
+    }
+
+    #[test]
+    fn condense_keeps_single_spaces_around_interpolation() {
+        let (ast, _warnings) = parse("<div>  {{ x }}  </div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children.len(), 1);
+        let text = root.children[0].borrow();
+        assert_eq!(text.el.token.data, Box::from(" {{ x }} "));
+        assert_eq!(text.el.expression.as_ref().unwrap(), "\" \"+_s(x)+\" \"");
+
+        assert_eq!(
+            interpolation_expression("<div>\n\t {{ x }}\n\n<b></b></div>"),
+            "\" \"+_s(x)+\" \""
+        );
+    }
+
+    #[test]
+    fn pre_keeps_raw_whitespace() {
+        let (ast, _warnings) = parse("<div><pre>  a   b\n</pre><p>  a   b\n</p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let pre = root.children[0].borrow();
+        let text = pre.children[0].borrow();
+        assert_eq!(text.el.token.data, Box::from("  a   b\n"));
+        assert!(text.el.in_pre);
+
+        let p = root.children[1].borrow();
+        let text = p.children[0].borrow();
+        assert_eq!(text.el.token.data, Box::from(" a b "));
+        assert!(!text.el.in_pre);
+    }
+
+    #[test]
+    fn keep_alive_with_multiple_children_warns() {
+        let (_ast, warnings) = parse("<keep-alive><a></a><b></b></keep-alive>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["<keep-alive> can only be used on a single element. Only its first child is rendered and cached."]
+        );
+
+        // a v-if chain renders a single element
+        let (_ast, warnings) = parse("<keep-alive><a v-if=\"x\"></a><b v-else></b></keep-alive>");
+        assert!(warnings.borrow().is_empty());
+    }
+
+    #[test]
+    fn v_if_with_v_for_warns() {
+        let (ast, warnings) = parse("<ul><li v-for=\"i in l\" v-if=\"i.ok\"></li></ul>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["<li>: v-if is used on the same element as v-for. v-for takes precedence, so the condition is evaluated for every item. Move the v-if to a wrapping <template> element or filter the list in a computed property instead."]
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let li = root.children[0].borrow();
+        assert_eq!(li.el.for_value.as_deref(), Some("l"));
+        assert_eq!(li.el.if_val.as_deref(), Some("i.ok"));
+
+        // the order of the attributes does not matter
+        let (_ast, warnings) = parse("<ul><li v-if=\"i.ok\" v-for=\"i in n\"></li></ul>");
+        assert_eq!(warnings.borrow().len(), 1);
+        assert!(
+            warnings.borrow()[0].starts_with("<li>: v-if is used on the same element as v-for.")
+        );
+    }
+
+    #[test]
+    fn is_on_template_is_ignored() {
+        let (ast, warnings) = parse("<div><template is=\"comp\"><p>a</p></template></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["<template> cannot be used with the \"is\" attribute, it is ignored. Use <component :is> instead."]
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let template = root.children[0].borrow();
+        assert_eq!(template.el.component, None);
+        assert!(template.el.attrs.is_empty());
+        assert!(!template.is_maybe_component());
+    }
+
+    #[test]
+    fn source_range_of_elements() {
+        let options = CompilerOptions {
+            output_source_range: true,
+            ..Default::default()
+        };
+        let ast =
+            VueParser::new(&options).parse("<div id=\"a\">\n  <input disabled><p>x</p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        // elements end with their close tag
+        assert_eq!(
+            root.el.range,
+            Some(SourceRange {
+                start: 0,
+                end: 45,
+                line: 1,
+                column: 1
+            })
+        );
+        assert_eq!(
+            root.children[0].borrow().el.range,
+            Some(SourceRange {
+                start: 15,
+                end: 31,
+                line: 2,
+                column: 3
+            })
+        );
+        assert_eq!(
+            root.children[1].borrow().el.range,
+            Some(SourceRange {
+                start: 31,
+                end: 39,
+                line: 2,
+                column: 19
+            })
+        );
+    }
+
+    #[test]
+    fn source_range_of_text() {
+        let options = CompilerOptions {
+            output_source_range: true,
+            ..Default::default()
+        };
+        let template = "<div><p>a &lt; {{ b }}</p><p>c</p></div>";
+        let ast = VueParser::new(&options).parse(template);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let source = |range: Option<SourceRange>| {
+            let range = range.unwrap();
+            &template[range.start..range.end]
+        };
+        // entities are decoded in the node text but the range covers them as written
+        let expression = root.children[0].borrow().children[0].clone();
+        assert_eq!(expression.borrow().el.kind, ASTElementKind::Expression);
+        assert_eq!(source(expression.borrow().el.range), "a &lt; {{ b }}");
+        let text = root.children[1].borrow().children[0].clone();
+        assert_eq!(
+            text.borrow().el.range,
+            Some(SourceRange {
+                start: 29,
+                end: 30,
+                line: 1,
+                column: 30
+            })
+        );
+    }
+
+    #[test]
+    fn source_range_line_and_column() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let warnings_clone = Rc::clone(&warnings);
+        let options = CompilerOptions {
+            dev: true,
+            output_source_range: true,
+            warn: Some(Box::new(RangeWarnLogger(
+                move |msg: &str, range: Option<SourceRange>| {
+                    warnings_clone.borrow_mut().push((msg.to_string(), range))
+                },
+            ))),
+            ..Default::default()
+        };
+        VueParser::new(&options).parse("<div>\n  <p>ä</p>\n  <i>ä</i><b v-for=\"x\"></b>\n</div>");
+
+        let warnings = warnings.borrow();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].0, "Invalid v-for expression: x");
+        let range = warnings[0].1.unwrap();
+        assert_eq!(range.line, 3);
+        // the column counts characters, not bytes
+        assert_eq!(range.column, 11);
+    }
+
+    #[test]
+    fn source_range_of_event_handlers() {
+        let options = CompilerOptions {
+            output_source_range: true,
+            ..Default::default()
+        };
+        let template =
+            "<div @click=\"onClick\" v-on:keyup.enter.native='onKey($event)' :a.sync=b></div>";
+        let ast = VueParser::new(&options).parse(template);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let source = |range: Option<SourceRange>| {
+            let range = range.unwrap();
+            &template[range.start..range.end]
+        };
+
+        let click = &root.el.events.as_ref().unwrap().get("click").unwrap()[0];
+        assert_eq!(
+            click.range,
+            Some(SourceRange {
+                start: 13,
+                end: 20,
+                line: 1,
+                column: 14
+            })
+        );
+        assert_eq!(source(click.range), "onClick");
+
+        let keyup = &root
+            .el
+            .native_events
+            .as_ref()
+            .unwrap()
+            .get("keyup")
+            .unwrap()[0];
+        assert_eq!(source(keyup.range), "onKey($event)");
+
+        let sync = &root.el.events.as_ref().unwrap().get("update:a").unwrap()[0];
+        assert_eq!(source(sync.range), "b");
+        assert_eq!(source(root.el.attr_ranges.get(":a.sync").copied()), "b");
+
+        // ranges are only recorded when asked for
+        let ast = VueParser::new(&CompilerOptions::default()).parse(template);
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert!(root.el.attr_ranges.is_empty());
+        assert_eq!(
+            root.el.events.as_ref().unwrap().get("click").unwrap()[0].range,
+            None
+        );
+    }
+
+    #[test]
+    fn template_v_for_key_warning() {
+        let (ast, warnings) =
+            parse("<div><template v-for=\"i in n\"><p>{{ i }}</p></template></div>");
+        assert!(warnings.borrow().is_empty());
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children[0].borrow().el.for_value.as_deref(), Some("n"));
+
+        let (_ast, warnings) =
+            parse("<div><template v-for=\"i in n\" :key=\"i\"><p>{{ i }}</p></template></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["<template> cannot be keyed. Place the key on real elements instead. key was i"]
+        );
+    }
+
+    #[test]
+    fn custom_entity_table() {
+        let options = CompilerOptions {
+            entities: Some(HashMap::from([("myentity".to_string(), "★".to_string())])),
+            ..Default::default()
+        };
+        let ast = VueParser::new(&options)
+            .parse("<div title=\"a &myentity; &amp; &other;\">&myentity; &lt; &other;</div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.attrs[0].value.as_deref(), Some("a ★ & &other;"));
+        assert_eq!(
+            root.children[0].borrow().el.token.data,
+            Box::from("★ < &other;")
+        );
+
+        // an escaped entity is decoded once and stays literal
+        let ast = VueParser::new(&options)
+            .parse("<div title=\"&amp;myentity; &myentity;\">&amp;myentity; &myentity;</div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.attrs[0].value.as_deref(), Some("&myentity; ★"));
+        assert_eq!(
+            root.children[0].borrow().el.token.data,
+            Box::from("&myentity; ★")
+        );
+    }
+
+    #[test]
+    fn tree_iterator_in_document_order() {
+        let (ast, _warnings) = parse(
+            "<div><ul><li>a</li><li v-if=\"x\">b</li><li v-else>c</li></ul><p>{{ d }}<span>e</span></p></div>",
+        );
+
+        let visited: Vec<String> = ast
+            .iter()
+            .map(|node| node.borrow().el.token.data.to_string())
+            .collect();
+        assert_eq!(
+            visited,
+            vec!["div", "ul", "li", "a", "li", "b", "li", "c", "p", "{{ d }}", "span", "e"]
+        );
+    }
+
+    #[test]
+    fn remove_node_from_tree() {
+        let (mut ast, _warnings) = parse("<div><p><b>a</b></p><span>b</span></div>");
+        let root_id = ast.wrapper.borrow().children[0].borrow().id;
+        let p_id = ast.get(root_id).unwrap().borrow().children[0].borrow().id;
+        let nodes_before = ast.all_nodes().len();
+
+        let removed = ast.remove(p_id).unwrap();
+        assert_eq!(removed.borrow().el.token.data, Box::from("p"));
+        assert!(ast.get(p_id).is_none());
+        // <p>, <b> and the text node
+        assert_eq!(ast.all_nodes().len(), nodes_before - 3);
+
+        let root = ast.get(root_id).unwrap().borrow();
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].borrow().el.token.data, Box::from("span"));
+        drop(root);
+
+        assert!(ast.remove(p_id).is_none());
+        assert!(ast.remove(0).is_none());
+    }
+
+    #[test]
+    fn remove_if_branches_and_scoped_slots() {
+        let (mut ast, _warnings) = parse(
+            "<div><p v-if=\"a\">a</p><p v-else-if=\"b\">b</p><p v-else>c</p><comp><template #item=\"{ x }\"><b>{{ x }}</b></template></comp></div>",
+        );
+        let root_id = ast.root_id.unwrap();
+        let (p_id, else_if_id, else_id, comp_id) = {
+            let root = ast.get(root_id).unwrap().borrow();
+            let comp_id = root.children[1].borrow().id;
+            let p = root.children[0].borrow();
+            let if_conditions = p.el.if_conditions.as_ref().unwrap();
+            (
+                p.id,
+                if_conditions[1].block_id,
+                if_conditions[2].block_id,
+                comp_id,
+            )
+        };
+        let nodes_before = ast.all_nodes().len();
+
+        // a branch is unlinked from the conditions of its v-if element
+        ast.remove(else_if_id).unwrap();
+        assert!(ast.get(else_if_id).is_none());
+        assert_eq!(ast.all_nodes().len(), nodes_before - 2);
+        {
+            let p = ast.get(p_id).unwrap().borrow();
+            let block_ids: Vec<usize> =
+                p.el.if_conditions
+                    .iter()
+                    .flatten()
+                    .map(|condition| condition.block_id)
+                    .collect();
+            assert_eq!(block_ids, vec![p_id, else_id]);
+        }
+
+        // the remaining branch goes with its v-if element
+        ast.remove(p_id).unwrap();
+        assert!(ast.get(else_id).is_none());
+        assert_eq!(ast.all_nodes().len(), nodes_before - 6);
+
+        // scoped slots are not children of the component
+        let slot_id = {
+            let comp = ast.get(comp_id).unwrap().borrow();
+            assert!(comp.children.is_empty());
+            let slot_id = comp
+                .el
+                .scoped_slots
+                .as_ref()
+                .unwrap()
+                .values()
+                .next()
+                .unwrap()
+                .borrow()
+                .id;
+            slot_id
+        };
+        ast.remove(comp_id).unwrap();
+        assert!(ast.get(slot_id).is_none());
+        assert_eq!(ast.all_nodes().len(), nodes_before - 10);
+        assert_eq!(ast.root_id, Some(root_id));
+    }
+
+    #[test]
+    fn remove_root_element() {
+        let (mut ast, _warnings) = parse("<div v-if=\"a\">a</div><p v-else>b</p>");
+        let root_id = ast.root_id.unwrap();
+        let else_id = ast
+            .get(root_id)
+            .unwrap()
+            .borrow()
+            .el
+            .if_conditions
+            .as_ref()
+            .unwrap()[1]
+            .block_id;
+
+        ast.remove(root_id).unwrap();
+        assert_eq!(ast.root_id, None);
+        assert!(ast.wrapper.borrow().children.is_empty());
+        assert!(ast.get(else_id).is_none());
+        // only the wrapper is left
+        assert_eq!(ast.all_nodes().len(), 1);
+    }
+
+    #[test]
+    fn content_directive_children_are_removed_from_tree() {
+        let (ast, _warnings) = parse(
+            "<div><p v-html=\"html\"><b>x</b></p><span v-text=\"msg\">text {{ a }}</span></div>",
+        );
+        // the div, p and span
+        assert_eq!(ast.iter().count(), 3);
+        // and the wrapper
+        assert_eq!(ast.all_nodes().len(), 4);
+    }
+
+    #[test]
+    fn trimmed_whitespace_is_removed_from_tree() {
+        let options = CompilerOptions {
+            trim_block_whitespace: true,
+            ..Default::default()
+        };
+        let ast = VueParser::new(&options).parse("<div><p>a</p> <p>b</p> <span>c</span></div>");
+        let tags: Vec<String> = ast
+            .iter()
+            .map(|node| node.borrow().el.token.data.to_string())
+            .collect();
+        assert_eq!(tags, vec!["div", "p", "a", "p", "b", "span", "c"]);
+        // the whitespace between the blocks is gone from the node map too
+        assert_eq!(ast.all_nodes().len(), tags.len() + 1);
+
+        // as is the whitespace at the start and end of an element
+        let (ast, _warnings) = parse("<div> <p> a </p> </div>");
+        assert_eq!(ast.iter().count(), 3);
+        assert_eq!(ast.all_nodes().len(), 4);
+    }
+
+    #[test]
+    fn process_constructed_node() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let warnings_clone = Rc::clone(&warnings);
+        let options = CompilerOptions {
+            dev: true,
+            warn: Some(Box::new(move |msg: &str| {
+                warnings_clone.borrow_mut().push(msg.to_string());
+            })),
+            modules: Some(vec![Box::new(ClassModule {})]),
+            ..Default::default()
+        };
+
+        let mut attrs = UniCaseBTreeMap::new();
+        for (name, value) in [
+            ("v-for", "item in items"),
+            (":key", "item.id"),
+            ("class", "row"),
+            (":class", "{ active: item.active }"),
+            ("@click", "select(item)"),
+            ("title", "x"),
+        ] {
+            attrs.insert(name, Some((Box::from(value), QuoteType::Double)));
+        }
+        let token = Token {
+            kind: TokenKind::OpenTag,
+            data: "li".into(),
+            attrs: Some(attrs),
+            is_implied: false,
+        };
+
+        let mut tree = ASTTree::new(options.dev, options.warn.clone().unwrap());
+        let node_rc = tree.create(
+            create_ast_element(token, ASTElementKind::Element),
+            0,
+            options.dev,
+            options.warn.clone().unwrap(),
+        );
+        let mut node = node_rc.borrow_mut();
+        node.process(&mut tree, &options);
+
+        assert_eq!(node.el.for_value.as_deref(), Some("items"));
+        assert_eq!(node.el.alias.as_deref(), Some("item"));
+        assert_eq!(node.el.key.as_deref(), Some("item.id"));
+        assert_eq!(node.el.static_class.as_deref(), Some("row"));
+        assert_eq!(
+            node.el.class_binding.as_deref(),
+            Some("{ active: item.active }")
+        );
+        assert_eq!(
+            node.el.events.as_ref().unwrap().get("click").unwrap()[0].value,
+            "select(item)"
+        );
+        assert_eq!(node.el.attrs.len(), 1);
+        assert_eq!(node.el.attrs[0].name, "title");
+        assert!(!node.el.plain);
+        assert!(node.el.processed);
+        assert!(warnings.borrow().is_empty());
+
+        // running it again does not process the attributes twice
+        node.process(&mut tree, &options);
+        assert_eq!(node.el.attrs.len(), 1);
+        assert_eq!(
+            node.el.events.as_ref().unwrap().get("click").unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn v_slot_shorthand_names() {
+        let (ast, warnings) = parse(
+            "<my-comp><template #foo=\"s\">{{ s }}</template><template #[bar]>b</template></my-comp>",
+        );
+        assert!(warnings.borrow().is_empty());
+        let wrapper = ast.wrapper.borrow();
+        let comp = wrapper.children[0].borrow();
+        let scoped_slots = comp.el.scoped_slots.as_ref().unwrap();
+
+        let foo = scoped_slots.get("\"foo\"").unwrap().borrow();
+        assert!(!foo.el.slot_target_dynamic);
+        assert_eq!(foo.el.slot_scope.as_deref(), Some("s"));
+        let bar = scoped_slots.get("bar").unwrap().borrow();
+        assert_eq!(bar.el.slot_target.as_deref(), Some("bar"));
+        assert!(bar.el.slot_target_dynamic);
+
+        let (_ast, warnings) = parse("<my-comp><template #>x</template></my-comp>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["v-slot shorthand syntax requires a slot name."]
+        );
+        let (_ast, warnings) = parse("<my-comp #>x</my-comp>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["v-slot shorthand syntax requires a slot name."]
+        );
+
+        // only reported in development
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let warnings_clone = Rc::clone(&warnings);
+        let options = CompilerOptions {
+            new_slot_syntax: true,
+            warn: Some(Box::new(move |msg: &str| {
+                warnings_clone.borrow_mut().push(msg.to_string());
+            })),
+            ..Default::default()
+        };
+        parse_with_options("<my-comp><template #>x</template></my-comp>", &options);
+        assert!(warnings.borrow().is_empty());
+    }
+
+    #[test]
+    fn mixed_slot_syntax_under_component_warns() {
+        let warning = "<my-comp>: Unexpected mixed usage of different slot syntaxes. Use either v-slot or the deprecated slot and slot-scope attributes for all the slots of a component.";
+
+        let (_ast, warnings) = parse(
+            "<my-comp><p slot=\"header\">h</p><template v-slot:footer>f</template></my-comp>",
+        );
+        assert_eq!(*warnings.borrow(), vec![warning]);
+
+        let (_ast, warnings) = parse(
+            "<my-comp><template slot=\"header\" slot-scope=\"s\">h</template><template #footer>f</template></my-comp>",
+        );
+        assert_eq!(*warnings.borrow(), vec![warning]);
+
+        // one syntax for all the slots
+        let (_ast, warnings) = parse(
+            "<my-comp><template #header>h</template><template #footer=\"s\">f</template></my-comp>",
+        );
+        assert!(warnings.borrow().is_empty());
+        let (_ast, warnings) = parse(
+            "<my-comp><p slot=\"header\">h</p><template slot=\"footer\" slot-scope=\"s\">f</template></my-comp>",
+        );
+        assert!(warnings.borrow().is_empty());
+    }
+
+    #[test]
+    fn new_slot_syntax_toggle() {
+        let template = "<my-comp><template #foo>x</template></my-comp>";
+        assert!(CompilerOptions::default().new_slot_syntax);
+
+        let ast = parse_with_options(template, &CompilerOptions::default());
+        let wrapper = ast.wrapper.borrow();
+        let comp = wrapper.children[0].borrow();
+        assert!(comp.children.is_empty());
+        let slot = comp
+            .el
+            .scoped_slots
+            .as_ref()
+            .unwrap()
+            .get("\"foo\"")
+            .unwrap()
+            .borrow();
+        assert!(slot.el.v_slot);
+
+        let options = CompilerOptions {
+            new_slot_syntax: false,
+            ..Default::default()
+        };
+        let ast = parse_with_options(template, &options);
+        let wrapper = ast.wrapper.borrow();
+        let comp = wrapper.children[0].borrow();
+        assert!(comp.el.scoped_slots.is_none());
+        assert_eq!(comp.children.len(), 1);
+        let child = comp.children[0].borrow();
+        assert_eq!(child.el.slot_target, None);
+        assert!(!child.el.v_slot);
+        // left to the runtime as an unknown directive
+        assert_eq!(child.el.directives.as_ref().unwrap()[0].raw_name, "#foo");
+    }
+
+    #[test]
+    fn sync_requires_assignable_expression() {
+        for valid in [
+            "a",
+            "a.b",
+            "a.b.c",
+            "a[b]",
+            "a['b'].c",
+            "a[b[0]]",
+            "$data.x_1",
+        ] {
+            let (_ast, warnings) = parse(&format!("<my-comp :foo.sync=\"{}\"></my-comp>", valid));
+            assert!(
+                warnings.borrow().is_empty(),
+                "{}: {:?}",
+                valid,
+                warnings.borrow()
+            );
+        }
+
+        let warning = |name: &str, value: &str| {
+            format!("{}=\"{}\": the .sync modifier requires an assignable expression such as a property path like a.b or a[b].", name, value)
+        };
+        for invalid in ["fn()", "a.b()", "a | f", "a + b", "a[]", "1"] {
+            let (_ast, warnings) = parse(&format!("<my-comp :foo.sync=\"{}\"></my-comp>", invalid));
+            assert_eq!(*warnings.borrow(), vec![warning(":foo.sync", invalid)]);
+        }
+        let (_ast, warnings) = parse("<my-comp v-bind:foo.sync=\" a.b() \"></my-comp>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec![warning("v-bind:foo.sync", "a.b()")]
+        );
+    }
+
+    #[test]
+    fn scoped_slot_templates_are_not_component_children() {
+        let (ast, _warnings) = parse("<div><my-comp><template #foo>x</template></my-comp></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let comp = root.children[0].borrow();
+        assert!(comp.children.is_empty());
+        let scoped_slots = comp.el.scoped_slots.as_ref().unwrap();
+        assert_eq!(scoped_slots.len(), 1);
+        let slot = scoped_slots.values().next().unwrap().borrow();
+        assert_eq!(slot.el.slot_target.as_deref(), Some("\"foo\""));
+        assert_eq!(slot.children.len(), 1);
+
+        // the slot container created for v-slot on the component itself
+        let (ast, _warnings) = parse("<div><my-comp v-slot=\"s\"><p>{{ s }}</p></my-comp></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let comp = root.children[0].borrow();
+        assert!(comp.children.is_empty());
+        assert_eq!(comp.el.scoped_slots.as_ref().unwrap().len(), 1);
+
+        // every node is reachable exactly once
+        let ids: Vec<usize> = ast.iter().map(|node| node.borrow().id).collect();
+        let unique_ids: HashSet<usize> = ids.iter().copied().collect();
+        assert_eq!(unique_ids.len(), ids.len());
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[test]
+    fn filters_ignore_pipes_outside_filters() {
+        assert_eq!(
+            interpolation_expression("<p>{{ a || b }}</p>"),
+            "_s(a || b)"
+        );
+        assert_eq!(
+            interpolation_expression("<p>{{ \"a|b\" }}</p>"),
+            "_s(\"a|b\")"
+        );
+        assert_eq!(
+            interpolation_expression("<p>{{ 'a|b' + `c|d` }}</p>"),
+            "_s('a|b' + `c|d`)"
+        );
+        assert_eq!(
+            interpolation_expression("<p>{{ /a|b/.test(c) }}</p>"),
+            "_s(/a|b/.test(c))"
+        );
+        assert_eq!(
+            interpolation_expression("<p>{{ a[b | c] + (d | e) }}</p>"),
+            "_s(a[b | c] + (d | e))"
+        );
+        assert_eq!(
+            interpolation_expression("<p>{{ msg | capitalize }}</p>"),
+            "_s(_f(\"capitalize\")(msg))"
+        );
+
+        let (ast, _warnings) = parse("<p :title=\"msg | capitalize\" :alt='a || b'></p>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let title = root
+            .el
+            .attrs
+            .iter()
+            .find(|attr| attr.name == "title")
+            .unwrap();
+        assert_eq!(title.value, Some("_f(\"capitalize\")(msg)".to_string()));
+        let alt = root
+            .el
+            .attrs
+            .iter()
+            .find(|attr| attr.name == "alt")
+            .unwrap();
+        assert_eq!(alt.value, Some("a || b".to_string()));
+    }
+
+    #[test]
+    fn filters_with_arguments() {
+        assert_eq!(
+            interpolation_expression("<p>{{ price | currency('$', 2) }}</p>"),
+            "_s(_f(\"currency\")(price,'$', 2))"
+        );
+        assert_eq!(
+            interpolation_expression("<p>{{ price | currency() }}</p>"),
+            "_s(_f(\"currency\")(price))"
+        );
+        assert_eq!(
+            interpolation_expression("<p>{{ a | b | c(1) | d }}</p>"),
+            "_s(_f(\"d\")(_f(\"c\")(_f(\"b\")(a),1)))"
+        );
+    }
+
+    #[test]
+    fn used_filters_are_reported() {
+        let (ast, _warnings) = parse(
+            "<div :title=\"t | upper\"><p>{{ price | currency('$') }}</p><span :class=\"c | upper\">{{ a || b }}</span></div>",
+        );
+
+        assert_eq!(
+            ast.stats.filters.iter().collect::<Vec<_>>(),
+            vec!["currency", "upper"]
+        );
+
+        let (ast, _warnings) = parse("<div>{{ a }}</div>");
+        assert!(ast.stats.filters.is_empty());
+    }
+
     #[test]
     fn child_elements() {
         let (ast, _warnings) = parse("<ul><li>hello world</li></ul>");
@@ -94,6 +895,191 @@ mod tests {
         assert_eq!(Rc::ptr_eq(&parent, &wrapper.children[0]), true);
     }
 
+    #[test]
+    fn self_referencing_component() {
+        let options = CompilerOptions {
+            component_name: Some("TreeItem".to_string()),
+            ..Default::default()
+        };
+        let ast = parse_with_options(
+            "<div><tree-item></tree-item><ul v-if=\"open\"><li><TreeItem></TreeItem></li></ul><other-item></other-item></div>",
+            &options,
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        let unguarded = root.children[0].borrow();
+        assert_eq!(unguarded.el.is_self_reference, true);
+        assert_eq!(unguarded.is_recursion_guarded(), false);
+
+        let guarded = root.children[1].borrow().children[0].borrow().children[0].clone();
+        assert_eq!(guarded.borrow().el.is_self_reference, true);
+        assert_eq!(guarded.borrow().is_recursion_guarded(), true);
+
+        assert_eq!(root.children[2].borrow().el.is_self_reference, false);
+    }
+
+    #[test]
+    fn max_depth_stat() {
+        let (ast, _warnings) =
+            parse("<div><p><span><b>deep</b></span></p><ul><li><br></li></ul></div>");
+        assert_eq!(ast.stats.max_depth, 4);
+
+        let (ast, _warnings) = parse("<div></div>");
+        assert_eq!(ast.stats.max_depth, 1);
+    }
+
+    struct RecordingTransform {
+        phase: &'static str,
+        calls: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl RecordingTransform {
+        fn record(&self, node: &ASTNode) {
+            self.calls
+                .borrow_mut()
+                .push(format!("{} {}", self.phase, node.el.token.data));
+        }
+    }
+
+    impl PreNodeTransform for RecordingTransform {
+        fn transform(
+            &self,
+            node: &mut ASTNode,
+            _tree: &mut ASTTree,
+            _options: &CompilerOptions,
+        ) -> Option<Rc<RefCell<ASTNode>>> {
+            self.record(node);
+
+            None
+        }
+    }
+
+    impl NodeTransform for RecordingTransform {
+        fn transform(&self, node: &mut ASTNode, _tree: &mut ASTTree, _options: &CompilerOptions) {
+            self.record(node);
+        }
+    }
+
+    // replaces <old-tag> elements with a <new-tag> element with the same attributes
+    struct ReplacingTransform;
+
+    impl PreNodeTransform for ReplacingTransform {
+        fn transform(
+            &self,
+            node: &mut ASTNode,
+            tree: &mut ASTTree,
+            _options: &CompilerOptions,
+        ) -> Option<Rc<RefCell<ASTNode>>> {
+            if &*node.el.token.data != "old-tag" {
+                return None;
+            }
+            let token = Token {
+                data: Box::from("new-tag"),
+                attrs: node.el.token.attrs.clone(),
+                kind: TokenKind::OpenTag,
+                is_implied: false,
+            };
+
+            Some(tree.create(
+                create_ast_element(token, ASTElementKind::Element),
+                node.parent_id,
+                node.is_dev,
+                node.warn.clone_box(),
+            ))
+        }
+    }
+
+    #[test]
+    fn no_op_transforms() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let recording = |phase| -> Option<Vec<Box<dyn NodeTransform>>> {
+            Some(vec![Box::new(RecordingTransform {
+                phase,
+                calls: Rc::clone(&calls),
+            })])
+        };
+        let options = CompilerOptions {
+            pre_transforms: Some(vec![Box::new(RecordingTransform {
+                phase: "pre",
+                calls: Rc::clone(&calls),
+            })]),
+            transforms: recording("transform"),
+            post_transforms: recording("post"),
+            ..Default::default()
+        };
+        let ast = parse_with_options("<div id=\"a\"><p v-if=\"ok\">text</p></div>", &options);
+
+        assert_eq!(
+            *calls.borrow(),
+            vec![
+                "pre div",
+                "pre p",
+                "transform p",
+                "post p",
+                "transform div",
+                "post div"
+            ]
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.attrs[0].name, "id");
+        let p = root.children[0].borrow();
+        assert_eq!(p.el.if_val, Some("ok".to_string()));
+    }
+
+    #[test]
+    fn pre_transform_replaces_node() {
+        let options = CompilerOptions {
+            pre_transforms: Some(vec![Box::new(ReplacingTransform)]),
+            ..Default::default()
+        };
+        let ast = parse_with_options("<div><old-tag id=\"a\">text</old-tag></div>", &options);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let replacement = root.children[0].borrow();
+        assert_eq!(replacement.el.token.data, Box::from("new-tag"));
+        assert_eq!(replacement.el.attrs[0].name, "id");
+        assert_eq!(
+            replacement.children[0].borrow().el.token.data,
+            Box::from("text")
+        );
+
+        // the replaced <old-tag> is not left in the tree
+        let tags: Vec<_> = ast
+            .all_nodes()
+            .iter()
+            .map(|node| node.borrow().el.token.data.to_string())
+            .collect();
+        assert_eq!(tags, vec!["", "div", "new-tag", "text"]);
+        assert!(ast
+            .iter()
+            .all(|node| &*node.borrow().el.token.data != "old-tag"));
+    }
+
+    #[test]
+    fn all_nodes_flat_list() {
+        let (ast, _warnings) =
+            parse("<div><p>hello</p><span></span><my-comp v-slot=\"s\">{{ s }}</my-comp></div>");
+
+        let nodes = ast.all_nodes();
+        // wrapper, div, p, text, span, my-comp, interpolation and the default slot container
+        assert_eq!(nodes.len(), 8);
+        for (index, node) in nodes.iter().enumerate() {
+            assert_eq!(node.borrow().id, index);
+            assert!(Rc::ptr_eq(node, ast.get(index).unwrap()));
+        }
+        assert_eq!(nodes[1].borrow().el.token.data, Box::from("div"));
+        assert_eq!(nodes[7].borrow().el.token.data, Box::from("template"));
+        assert_eq!(
+            nodes[7].borrow().el.slot_target.as_ref().unwrap(),
+            "\"default\""
+        );
+    }
+
     #[test]
     fn unary_element() {
         let (ast, _warnings) = parse("<hr>");
@@ -106,24 +1092,292 @@ mod tests {
     }
 
     #[test]
-    fn svg_element() {
-        let (ast, _warnings) = parse("<svg><text>hello world</text></svg>");
+    fn mismatched_closing_tags_auto_close() {
+        let (ast, warnings) = parse("<section><p><div></p></div></section>");
+
+        let wrapper = ast.wrapper.borrow();
+        assert_eq!(wrapper.children.len(), 1);
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.token.data, Box::from("section"));
+        assert_eq!(root.children.len(), 2);
+
+        // <div> implicitly closes the open <p>
+        let p = root.children[0].borrow();
+        assert_eq!(p.el.token.data, Box::from("p"));
+        assert_eq!(p.children.len(), 0);
+
+        // the stray </p> becomes an empty <p> inside the <div>
+        let div = root.children[1].borrow();
+        assert_eq!(div.el.token.data, Box::from("div"));
+        assert_eq!(div.children.len(), 1);
+        assert_eq!(div.children[0].borrow().el.token.data, Box::from("p"));
+
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn comments_preserved_with_option() {
+        let options = CompilerOptions {
+            preserve_comments: true,
+            ..Default::default()
+        };
+        let ast = parse_with_options("<!-- before --><div><!-- hi --></div>", &options);
+
+        let wrapper = ast.wrapper.borrow();
+        assert_eq!(wrapper.children.len(), 1);
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.token.data, Box::from("div"));
+        assert_eq!(root.children.len(), 1);
+
+        let comment = root.children[0].borrow();
+        assert_eq!(comment.el.kind, ASTElementKind::Text);
+        assert_eq!(comment.el.is_comment, true);
+        assert_eq!(comment.el.token.data, Box::from(" hi "));
+    }
+
+    #[test]
+    fn comments_dropped_by_default() {
+        let (ast, warnings) = parse("<div><!-- hi --></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children.len(), 0);
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn doctype_and_cdata() {
+        let (ast, warnings) = parse("<!DOCTYPE html><div><![CDATA[a < b]]><?php echo 1 ?></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        assert_eq!(wrapper.children.len(), 1);
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.token.data, Box::from("div"));
+        assert_eq!(root.children.len(), 1);
+
+        let text = root.children[0].borrow();
+        assert_eq!(text.el.kind, ASTElementKind::Text);
+        assert_eq!(text.el.is_comment, false);
+        assert_eq!(text.el.token.data, Box::from("a < b"));
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn warn_unmatched_tags() {
+        let (ast, warnings) = parse("<div><span><b></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec![
+                "tag <b> has no matching end tag.",
+                "tag <span> has no matching end tag."
+            ]
+        );
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children[0].borrow().children.len(), 1);
+
+        let (_ast, warnings) = parse("<div></span><p></p></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["tag </span> has no matching start tag."]
+        );
+
+        // elements left open at the end of the template
+        let (_ast, warnings) = parse("<div><span>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec![
+                "tag <span> has no matching end tag.",
+                "tag <div> has no matching end tag."
+            ]
+        );
+
+        // elements html closes on its own and close tags of void elements are fine
+        let (_ast, warnings) =
+            parse("<div><ul><li>a<li>b</li></ul><p>c<div></div><img></img></div>");
+        assert!(warnings.borrow().is_empty());
+    }
+
+    #[test]
+    fn stray_close_tag_range() {
+        let warnings = Rc::new(RefCell::new(Vec::new()));
+        let warnings_clone = Rc::clone(&warnings);
+        let options = CompilerOptions {
+            dev: true,
+            output_source_range: true,
+            warn: Some(Box::new(RangeWarnLogger(
+                move |msg: &str, range: Option<SourceRange>| {
+                    warnings_clone.borrow_mut().push((msg.to_string(), range))
+                },
+            ))),
+            ..Default::default()
+        };
+        VueParser::new(&options).parse("<div>\n  </span></div>");
+
+        let warnings = warnings.borrow();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].0, "tag </span> has no matching start tag.");
+        assert_eq!(
+            warnings[0].1,
+            Some(SourceRange {
+                start: 8,
+                end: 15,
+                line: 2,
+                column: 3
+            })
+        );
+    }
+
+    #[test]
+    fn unary_elements_inside_parent() {
+        let options = CompilerOptions {
+            is_unary_tag: Some(|tag| matches!(tag, "br" | "img" | "my-icon")),
+            ..Default::default()
+        };
+        let ast = parse_with_options(
+            "<div><br><img src=x><my-icon/><span>text</span></div>",
+            &options,
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children.len(), 4);
+
+        let br = root.children[0].borrow();
+        assert_eq!(br.el.token.data, Box::from("br"));
+        assert_eq!(br.el.plain, true);
+        assert_eq!(br.children.len(), 0);
+
+        let img = root.children[1].borrow();
+        assert_eq!(img.el.token.data, Box::from("img"));
+        assert_eq!(img.el.attrs[0].name, "src");
+        assert_eq!(img.el.attrs[0].value, Some("x".to_string()));
+        assert_eq!(img.children.len(), 0);
+
+        // self-closing custom tag does not swallow its following siblings
+        let icon = root.children[2].borrow();
+        assert_eq!(icon.el.token.data, Box::from("my-icon"));
+        assert_eq!(icon.children.len(), 0);
+
+        let span = root.children[3].borrow();
+        assert_eq!(span.el.token.data, Box::from("span"));
+        assert_eq!(span.children.len(), 1);
+    }
+
+    #[test]
+    fn implied_close_tags() {
+        let (ast, _warnings) = parse("<div><ul><li>a<li>b</ul><p>c<div>d</div></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children.len(), 3);
+
+        // an <li> closes the previous one
+        let ul = root.children[0].borrow();
+        assert_eq!(ul.children.len(), 2);
+        for (li, text) in ul.children.iter().zip(["a", "b"]) {
+            let li = li.borrow();
+            assert_eq!(li.el.token.data, Box::from("li"));
+            assert_eq!(li.children[0].borrow().el.token.data, Box::from(text));
+        }
+
+        // a block element closes the open <p>, the <div> is its sibling
+        let p = root.children[1].borrow();
+        assert_eq!(p.el.token.data, Box::from("p"));
+        assert_eq!(p.children.len(), 1);
+        let div = root.children[2].borrow();
+        assert_eq!(div.el.token.data, Box::from("div"));
+        assert_eq!(div.children[0].borrow().el.token.data, Box::from("d"));
+    }
+
+    #[test]
+    fn svg_element() {
+        let (ast, _warnings) = parse("<svg><text>hello world</text></svg>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.token.data, Box::from("svg"));
+        assert_eq!(root.el.ns.as_deref(), Some("svg"));
+        assert_eq!(root.el.plain, true);
+
+        let child = root.children[0].borrow();
+        assert_eq!(child.el.token.data, Box::from("text"));
+        assert_eq!(
+            child.children[0].borrow().el.token.data,
+            Box::from("hello world")
+        );
+
+        let parent = child.parent.as_ref().unwrap().upgrade().unwrap();
+        assert_eq!(Rc::ptr_eq(&parent, &wrapper.children[0]), true);
+    }
+
+    #[test]
+    fn svg_element_namespace_inherited() {
+        let (ast, _warnings) = parse("<div><svg><path/></svg><p></p><math><mi>x</mi></math></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.ns, None);
+
+        let svg = root.children[0].borrow();
+        assert_eq!(svg.el.ns.as_deref(), Some("svg"));
+        let path = svg.children[0].borrow();
+        assert_eq!(path.el.token.data, Box::from("path"));
+        assert_eq!(path.el.ns.as_deref(), Some("svg"));
+
+        // siblings after the closed svg are not in the svg namespace
+        let p = root.children[1].borrow();
+        assert_eq!(p.el.ns, None);
+
+        let math = root.children[2].borrow();
+        assert_eq!(math.el.ns.as_deref(), Some("math"));
+        assert_eq!(math.children[0].borrow().el.ns.as_deref(), Some("math"));
+    }
+
+    #[test]
+    fn foreign_object_resets_namespace() {
+        let (ast, _warnings) =
+            parse("<svg><foreignObject><div><span></span></div></foreignObject></svg>");
+
+        let wrapper = ast.wrapper.borrow();
+        let svg = wrapper.children[0].borrow();
+        assert_eq!(svg.el.ns.as_deref(), Some("svg"));
+
+        let foreign_object = svg.children[0].borrow();
+        assert_eq!(foreign_object.el.ns.as_deref(), Some("svg"));
+
+        let div = foreign_object.children[0].borrow();
+        assert_eq!(div.el.token.data, Box::from("div"));
+        assert_eq!(div.el.ns, None);
+        assert_eq!(div.children[0].borrow().el.ns, None);
+    }
+
+    #[test]
+    fn custom_get_tag_namespace_option() {
+        let options = CompilerOptions {
+            get_tag_namespace: Some(|tag| {
+                if tag == "custom" {
+                    Some("custom-ns")
+                } else {
+                    None
+                }
+            }),
+            ..Default::default()
+        };
+        let ast = parse_with_options(
+            "<div><custom><span></span></custom><svg></svg></div>",
+            &options,
+        );
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.token.data, Box::from("svg"));
-        assert_eq!(root.el.ns, Some("svg"));
-        assert_eq!(root.el.plain, true);
-
-        let child = root.children[0].borrow();
-        assert_eq!(child.el.token.data, Box::from("text"));
+        let custom = root.children[0].borrow();
+        assert_eq!(custom.el.ns.as_deref(), Some("custom-ns"));
         assert_eq!(
-            child.children[0].borrow().el.token.data,
-            Box::from("hello world")
+            custom.children[0].borrow().el.ns.as_deref(),
+            Some("custom-ns")
         );
-
-        let parent = child.parent.as_ref().unwrap().upgrade().unwrap();
-        assert_eq!(Rc::ptr_eq(&parent, &wrapper.children[0]), true);
+        assert_eq!(root.children[1].borrow().el.ns, None);
     }
 
     #[test]
@@ -232,6 +1486,55 @@ mod tests {
         assert_eq!(warnings.borrow()[0], "Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.");
     }
 
+    #[test]
+    fn multiple_root_elements_keep_only_the_first_root() {
+        let (ast, warnings) = parse("<div id=\"a\"></div><div id=\"b\"></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        assert_eq!(wrapper.children.len(), 1);
+        assert_eq!(
+            wrapper.children[0].borrow().el.attrs[0].value,
+            Some("a".to_string())
+        );
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "Component template should contain exactly one root element. If you are using v-if on multiple elements, use v-else-if to chain them instead.");
+
+        let (ast, warnings) = parse("<div v-if=\"a\"></div><div v-else></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        assert_eq!(wrapper.children.len(), 1);
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.if_conditions.as_ref().unwrap().len(), 2);
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn allow_multiple_root_elements() {
+        let options = CompilerOptions {
+            dev: true,
+            allow_multiple_roots: true,
+            ..Default::default()
+        };
+        let ast = parse_with_options(
+            "<div id=\"a\"></div>\n<template v-for=\"x in xs\"><p></p></template><p v-if=\"a\"></p><p v-else></p>",
+            &options,
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        assert_eq!(wrapper.children.len(), 3);
+        assert_eq!(
+            wrapper.children[0].borrow().el.attrs[0].value,
+            Some("a".to_string())
+        );
+        assert_eq!(
+            wrapper.children[1].borrow().el.token.data,
+            Box::from("template")
+        );
+        let if_root = wrapper.children[2].borrow();
+        assert_eq!(if_root.el.if_conditions.as_ref().unwrap().len(), 2);
+        assert!(ast.warnings.is_empty());
+    }
+
     // Condensing white space could be moved to the html parser
     #[test]
     fn remove_duplicate_whitespace_text_nodes_caused_by_comments() {
@@ -251,6 +1554,31 @@ mod tests {
         assert_eq!(child_3.el.token.data, Box::from("a"));
     }
 
+    #[test]
+    fn forbidden_script_with_bound_type() {
+        let is_forbidden = |template: &str| {
+            let (ast, _warnings) = parse(template);
+            let wrapper = ast.wrapper.borrow();
+            let forbidden = wrapper.children[0].borrow().el.forbidden;
+            forbidden
+        };
+
+        assert!(is_forbidden(
+            "<script :type=\"'text/javascript'\">a()</script>"
+        ));
+        assert!(is_forbidden(
+            "<script v-bind:type=\"`text/javascript`\">a()</script>"
+        ));
+        // the value of other expressions is not known at compile time
+        assert!(is_forbidden("<script :type=\"scriptType\">a()</script>"));
+        assert!(!is_forbidden(
+            "<script :type=\"'text/x-template'\">a()</script>"
+        ));
+        assert!(!is_forbidden(
+            "<script type=\"text/x-template\">a()</script>"
+        ));
+    }
+
     #[test]
     fn forbidden_element_2() {
         // style
@@ -530,70 +1858,447 @@ mod tests {
 
         assert_eq!(warnings.borrow().len(), 1);
         assert_eq!(
-            warnings.borrow()[0],
-            "Cannot use <slot> as component root element because it may contain multiple nodes."
+            warnings.borrow()[0],
+            "Cannot use <slot> as component root element because it may contain multiple nodes."
+        );
+    }
+
+    #[test]
+    fn warn_v_for_on_root_element() {
+        let (_ast, warnings) = parse("<div v-for=\"item in items\"></div>");
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "Cannot use v-for on stateful component root element because it renders multiple elements.");
+    }
+
+    #[test]
+    fn warn_template_key() {
+        let (_ast, warnings) =
+            parse("<div><template v-for=\"i in 10\" :key=\"i\"></template></div>");
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(
+            warnings.borrow()[0],
+            "<template> cannot be keyed. Place the key on real elements instead. key was i"
+        );
+    }
+
+    #[test]
+    fn warn_the_child_of_the_transition_group_component_has_sequential_index() {
+        let (_ast, warnings) = parse("<div><transition-group><i v-for=\"(o, i) of arr\" :key=\"i\"></i></transition-group></div>");
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "Do not use v-for index as key on <transition-group> children,\nthis is the same as not using keys.");
+    }
+
+    #[test]
+    fn v_pre_directive() {
+        let (ast, _warnings) = parse("<div v-pre id=\"message1\"><p>{{msg}}</p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.pre, true);
+        assert_eq!(root.el.attrs[0].name, "id");
+        assert_eq!(root.el.attrs[0].value, Some("message1".to_string()));
+        assert_eq!(
+            root.children[0].borrow().children[0].borrow().el.token.data,
+            Box::from("{{msg}}")
+        );
+    }
+
+    #[test]
+    fn nested_v_pre_and_pre() {
+        let (ast, _warnings) =
+            parse("<div><div v-pre><span v-pre></span>{{x}}</div><p>{{y}}</p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let v_pre = root.children[0].borrow();
+        assert!(v_pre.el.pre);
+        // closing the inner element does not end the outer v-pre
+        let text = v_pre.children[1].borrow();
+        assert_eq!(text.el.kind, ASTElementKind::Text);
+        assert_eq!(text.el.token.data, Box::from("{{x}}"));
+        // the siblings after the v-pre element are compiled again
+        let p = root.children[1].borrow();
+        assert_eq!(p.children[0].borrow().el.kind, ASTElementKind::Expression);
+
+        let (ast, _warnings) = parse("<div><pre><pre>a</pre>  b\n</pre>  c\n</div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let outer_pre = root.children[0].borrow();
+        assert_eq!(
+            outer_pre.children[1].borrow().el.token.data,
+            Box::from("  b\n")
+        );
+        assert_eq!(root.children[1].borrow().el.token.data, Box::from(" c "));
+    }
+
+    #[test]
+    fn v_pre_keeps_raw_attributes_literally() {
+        let (ast, warnings) =
+            parse("<div v-pre :foo=\"bar\" class=\"x\" disabled><span @click=\"go\"></span></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let attrs: Vec<(&str, Option<&str>)> = root
+            .el
+            .attrs
+            .iter()
+            .map(|attr| (attr.name.as_str(), attr.value.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                (":foo", Some("bar")),
+                ("class", Some("x")),
+                ("disabled", None)
+            ]
+        );
+        assert_eq!(root.el.dynamic_attrs.len(), 0);
+        assert_eq!(root.el.static_class, None);
+        assert_eq!(root.el.has_bindings, false);
+
+        let span = root.children[0].borrow();
+        assert_eq!(span.el.attrs[0].name, "@click");
+        assert!(span.el.events.is_none());
+        assert_eq!(warnings.borrow().len(), 0);
+    }
+
+    #[test]
+    fn v_pre_directive_should_leave_template_in_dom() {
+        let (ast, _warnings) = parse(
+            "<div v-pre id=\"message1\"><template id=\"template1\"><p>{{msg}}</p></template></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.pre, true);
+        assert_eq!(root.el.attrs.len(), 1); // should be 1 but we can probably deal with it later
+        assert_eq!(root.el.attrs[0].name, "id");
+        assert_eq!(root.el.attrs[0].value, Some("message1".to_string()));
+        assert_eq!(root.children[0].borrow().el.attrs[0].name, "id");
+        assert_eq!(
+            root.children[0].borrow().el.attrs[0].value,
+            Some("template1".to_string())
+        );
+    }
+
+    #[test]
+    fn children_normalization_type() {
+        let (ast, _warnings) = parse(
+            "<div><ul><li v-for=\"i in items\"></li></ul><p><my-comp></my-comp></p><p><span v-if=\"a\"></span><template v-else>b</template></p><span>text</span></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let normalization_types: Vec<NormalizationType> = root
+            .children
+            .iter()
+            .map(|child| child.borrow().el.normalization_type)
+            .collect();
+        assert_eq!(
+            normalization_types,
+            vec![
+                NormalizationType::Full,
+                NormalizationType::Simple,
+                NormalizationType::Full,
+                NormalizationType::None,
+            ]
+        );
+        assert_eq!(root.el.normalization_type, NormalizationType::None);
+    }
+
+    #[test]
+    fn attr_disposition_consumed_and_kept() {
+        let (ast, _warnings) = parse("<div><p v-if=\"ok\" id=\"a\" :title=\"t\"></p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let disposition = root.children[0].borrow().attr_disposition();
+
+        assert_eq!(disposition.consumed, vec!["v-if"]);
+        assert_eq!(disposition.kept, vec!["id", ":title"]);
+    }
+
+    #[test]
+    fn add_attr_helper() {
+        let (ast, _warnings) = parse("<div></div>");
+        let wrapper = ast.wrapper.borrow();
+        let mut root = wrapper.children[0].borrow_mut();
+        assert_eq!(root.el.plain, true);
+
+        root.add_attr("id", Some("\"app\"".to_string()), false);
+        root.add_attr("name", Some("n".to_string()), true);
+
+        assert_eq!(root.el.plain, false);
+        assert_eq!(root.el.attrs.len(), 1);
+        assert_eq!(root.el.attrs[0].name, "id");
+        assert_eq!(root.el.attrs[0].value, Some("\"app\"".to_string()));
+        assert_eq!(root.el.dynamic_attrs.len(), 1);
+        assert_eq!(root.el.dynamic_attrs[0].name, "name");
+        assert_eq!(root.el.dynamic_attrs[0].dynamic, true);
+        // the raw token attributes are left untouched
+        assert!(root
+            .el
+            .token
+            .attrs
+            .as_ref()
+            .is_none_or(|attrs| attrs.is_empty()));
+    }
+
+    #[test]
+    fn add_prop_helper() {
+        let (ast, _warnings) = parse("<div></div>");
+        let wrapper = ast.wrapper.borrow();
+        let mut root = wrapper.children[0].borrow_mut();
+
+        root.add_prop("textContent", Some("msg".to_string()), false);
+
+        assert_eq!(root.el.plain, false);
+        assert_eq!(root.el.props.len(), 1);
+        assert_eq!(root.el.props[0].name, "textContent");
+        assert_eq!(root.el.props[0].value, Some("msg".to_string()));
+        assert_eq!(root.el.props[0].dynamic, false);
+    }
+
+    #[test]
+    fn prop_modifier_bindings() {
+        let options = CompilerOptions {
+            v_bind_prop_short_hand: true,
+            ..Default::default()
+        };
+        let ast = parse_with_options(
+            "<div .foo=\"bar\" :baz.prop=\"q\" :text-content.prop=\"t\" :inner-html.prop=\"h\" :[k].prop=\"d\" title=\"x\"></div>",
+            &options,
+        );
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        let props: Vec<(&str, Option<&str>, bool)> = root
+            .el
+            .props
+            .iter()
+            .map(|prop| (prop.name.as_str(), prop.value.as_deref(), prop.dynamic))
+            .collect();
+        assert_eq!(
+            props,
+            vec![
+                ("foo", Some("bar"), false),
+                ("baz", Some("q"), false),
+                ("textContent", Some("t"), false),
+                ("innerHTML", Some("h"), false),
+                ("k", Some("d"), true),
+            ]
+        );
+        assert_eq!(root.el.attrs.len(), 1);
+        assert_eq!(root.el.attrs[0].name, "title");
+
+        // without the shorthand option `.foo` is a plain attribute
+        let (ast, _warnings) = parse("<div .foo=\"bar\"></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert!(root.el.props.is_empty());
+        assert_eq!(root.el.attrs[0].name, ".foo");
+    }
+
+    #[test]
+    fn add_handler_helper() {
+        let (ast, _warnings) = parse("<div></div>");
+        let wrapper = ast.wrapper.borrow();
+        let mut root = wrapper.children[0].borrow_mut();
+
+        root.add_handler("click", "a", None, false, false, None);
+        root.add_handler("click", "b", None, true, false, None);
+
+        assert_eq!(root.el.plain, false);
+        let handlers = root.el.events.as_ref().unwrap().get("click").unwrap();
+        assert_eq!(handlers.len(), 2);
+        // important handlers go first
+        assert_eq!(handlers[0].value, "b");
+        assert_eq!(handlers[1].value, "a");
+    }
+
+    #[test]
+    fn add_directive_helper() {
+        let (ast, _warnings) = parse("<div></div>");
+        let wrapper = ast.wrapper.borrow();
+        let mut root = wrapper.children[0].borrow_mut();
+
+        root.add_directive(
+            "focus",
+            "v-focus:arg",
+            Some("ok".to_string()),
+            Some("arg"),
+            false,
+            None,
+        );
+
+        assert_eq!(root.el.plain, false);
+        let directives = root.el.directives.as_ref().unwrap();
+        assert_eq!(directives.len(), 1);
+        assert_eq!(directives[0].name, "focus");
+        assert_eq!(directives[0].raw_name, "v-focus:arg");
+        assert_eq!(directives[0].value, Some("ok".to_string()));
+        assert_eq!(directives[0].arg, Some("arg".to_string()));
+    }
+
+    #[test]
+    fn runtime_directives() {
+        let (ast, _warnings) = parse("<div v-show=\"ok\" v-cloak></div>");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+
+        let directives = root.el.directives.as_ref().unwrap();
+        assert_eq!(directives.len(), 2);
+        assert_eq!(directives[0].name, "show");
+        assert_eq!(directives[0].raw_name, "v-show");
+        assert_eq!(directives[0].value, Some("ok".to_string()));
+        assert_eq!(directives[0].arg, None);
+        assert!(directives[0].modifiers.is_empty());
+        assert_eq!(directives[1].name, "cloak");
+        assert_eq!(directives[1].raw_name, "v-cloak");
+        assert_eq!(directives[1].value, None);
+        // unlike v-if, v-show is not compiled away
+        assert_eq!(root.el.if_val, None);
+        assert!(!root.el.plain);
+    }
+
+    #[test]
+    fn key_absent_vs_empty() {
+        let (ast, _warnings) = parse("<div><p></p><p :key=\"\"></p><p key=\"a\"></p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children[0].borrow().el.key, None);
+        assert_eq!(root.children[1].borrow().el.key, Some("".to_string()));
+        assert_eq!(root.children[2].borrow().el.key, Some("\"a\"".to_string()));
+    }
+
+    #[test]
+    fn void_elements_without_close_tag() {
+        for tag in [
+            "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param",
+            "source", "track", "wbr",
+        ] {
+            let (ast, warnings) = parse(&format!("<div><{} id=\"a\"><p>x</p></div>", tag));
+            assert!(
+                warnings.borrow().is_empty(),
+                "<{}>: {:?}",
+                tag,
+                warnings.borrow()
+            );
+
+            let wrapper = ast.wrapper.borrow();
+            let root = wrapper.children[0].borrow();
+            assert_eq!(root.children.len(), 2, "<{}>", tag);
+            let void_element = root.children[0].borrow();
+            assert_eq!(&*void_element.el.token.data, tag);
+            assert!(void_element.children.is_empty(), "<{}>", tag);
+            assert_eq!(&*root.children[1].borrow().el.token.data, "p");
+        }
+    }
+
+    #[test]
+    fn key_with_filters() {
+        let (ast, _warnings) = parse(
+            "<div><p v-for=\"a in l\" :key=\"a | b\"></p><p v-for=\"c in l\" v-bind:key=\"c | d(1)\"></p><p v-if=\"ok\" key=\"k | e\"></p></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(
+            root.children[0].borrow().el.key.as_deref(),
+            Some("_f(\"b\")(a)")
+        );
+        assert_eq!(
+            root.children[1].borrow().el.key.as_deref(),
+            Some("_f(\"d\")(c,1)")
+        );
+        // a static key is a plain string, the pipe is not a filter
+        assert_eq!(
+            root.children[2].borrow().el.key.as_deref(),
+            Some("\"k | e\"")
+        );
+        assert_eq!(root.children[0].borrow().el.filters, vec!["b"]);
+        assert_eq!(root.children[1].borrow().el.filters, vec!["d"]);
+        assert!(root.children[2].borrow().el.filters.is_empty());
+    }
+
+    #[test]
+    fn key_recorded_in_production() {
+        let options = CompilerOptions::default();
+        let ast = parse_with_options("<div><p :key=\"item.id\"></p></div>", &options);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(
+            root.children[0].borrow().el.key,
+            Some("item.id".to_string())
         );
     }
 
     #[test]
-    fn warn_v_for_on_root_element() {
-        let (_ast, warnings) = parse("<div v-for=\"item in items\"></div>");
-
+    fn warn_static_key_without_effect() {
+        let (_ast, warnings) = parse("<div><p key=\"a\"></p></div>");
         assert_eq!(warnings.borrow().len(), 1);
-        assert_eq!(warnings.borrow()[0], "Cannot use v-for on stateful component root element because it renders multiple elements.");
+        assert_eq!(warnings.borrow()[0], "key=\"a\" on <p> has no effect, keys are only needed on elements rendered with v-for, v-if or inside a <transition>.");
+
+        let (_ast, warnings) = parse(
+            "<div><p v-if=\"ok\" key=\"a\"></p><p v-else key=\"b\"></p><transition><i key=\"c\"></i></transition><b :key=\"d\"></b></div>",
+        );
+        assert_eq!(warnings.borrow().len(), 0);
     }
 
     #[test]
-    fn warn_template_key() {
-        let (_ast, warnings) =
-            parse("<div><template v-for=\"i in 10\" :key=\"i\"></template></div>");
+    fn warn_ref_and_key_on_abstract_elements() {
+        let (_ast, warnings) = parse("<div><slot ref=\"x\"></slot></div>");
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "`ref` does not work on <slot> because slots are abstract outlets and can possibly expand into multiple elements. Use the ref on a wrapping element instead.");
 
+        let (_ast, warnings) = parse("<div><template ref=\"y\"><p></p></template></div>");
         assert_eq!(warnings.borrow().len(), 1);
         assert_eq!(
             warnings.borrow()[0],
-            "<template> cannot be keyed. Place the key on real elements instead. key was i"
+            "<template> cannot have a ref. Place the ref on real elements instead."
         );
-    }
-
-    #[test]
-    fn warn_the_child_of_the_transition_group_component_has_sequential_index() {
-        let (_ast, warnings) = parse("<div><transition-group><i v-for=\"(o, i) of arr\" :key=\"i\"></i></transition-group></div>");
 
+        let (_ast, warnings) = parse("<div><slot :key=\"k\"></slot></div>");
         assert_eq!(warnings.borrow().len(), 1);
-        assert_eq!(warnings.borrow()[0], "Do not use v-for index as key on <transition-group> children,\nthis is the same as not using keys.");
+        assert_eq!(warnings.borrow()[0], "`key` does not work on <slot> because slots are abstract outlets and can possibly expand into multiple elements. Use the key on a wrapping element instead.");
+
+        let (_ast, warnings) = parse("<div><p ref=\"z\"></p></div>");
+        assert_eq!(warnings.borrow().len(), 0);
     }
 
     #[test]
-    fn v_pre_directive() {
-        let (ast, _warnings) = parse("<div v-pre id=\"message1\"><p>{{msg}}</p></div>");
-
+    fn v_for_auto_key_only_when_enabled() {
+        let (ast, _warnings) = parse("<ul><li v-for=\"(item, i) in items\"></li></ul>");
         let wrapper = ast.wrapper.borrow();
-        let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.pre, true);
-        assert_eq!(root.el.attrs[0].name, "id");
-        assert_eq!(root.el.attrs[0].value, Some("message1".to_string()));
-        assert_eq!(
-            root.children[0].borrow().children[0].borrow().el.token.data,
-            Box::from("{{msg}}")
-        );
-    }
+        let li = wrapper.children[0].borrow().children[0].clone();
+        assert_eq!(li.borrow().el.key, None);
 
-    #[test]
-    fn v_pre_directive_should_leave_template_in_dom() {
-        let (ast, _warnings) = parse(
-            "<div v-pre id=\"message1\"><template id=\"template1\"><p>{{msg}}</p></template></div>",
+        let options = CompilerOptions {
+            auto_key_v_for: true,
+            ..Default::default()
+        };
+        let ast = parse_with_options(
+            "<ul><li v-for=\"(item, i) in items\"></li><p v-for=\"item in items\"></p><b v-for=\"item in items\" :key=\"item.id\"></b></ul>",
+            &options,
         );
-
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.pre, true);
-        assert_eq!(root.el.attrs.len(), 1); // should be 1 but we can probably deal with it later
-        assert_eq!(root.el.attrs[0].name, "id");
-        assert_eq!(root.el.attrs[0].value, Some("message1".to_string()));
-        assert_eq!(root.children[0].borrow().el.attrs[0].name, "id");
+
+        assert_eq!(root.children[0].borrow().el.key, Some("i".to_string()));
+
+        let p = root.children[1].borrow();
+        assert_eq!(p.el.iterator1, Some("$index".to_string()));
+        assert_eq!(p.el.key, Some("$index".to_string()));
+
         assert_eq!(
-            root.children[0].borrow().el.attrs[0].value,
-            Some("template1".to_string())
+            root.children[2].borrow().el.key,
+            Some("item.id".to_string())
         );
     }
 
@@ -810,179 +2515,643 @@ mod tests {
     }
 
     #[test]
-    fn v_for_directive_invalid_syntax() {
-        let (_ast, warnings) = parse("<ul><li v-for=\"item into items\"></li></ul>");
+    fn v_for_directive_invalid_syntax() {
+        let (_ast, warnings) = parse("<ul><li v-for=\"item into items\"></li></ul>");
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(
+            warnings.borrow()[0],
+            "Invalid v-for expression: item into items"
+        );
+    }
+
+    #[test]
+    fn v_for_expression_forms() {
+        let for_parts = |template: &str| {
+            let (ast, warnings) = parse(template);
+            let wrapper = ast.wrapper.borrow();
+            let root = wrapper.children[0].borrow();
+            let li = root.children[0].borrow();
+            let warnings = warnings.borrow().clone();
+
+            (
+                li.el.alias.clone(),
+                li.el.for_value.clone(),
+                li.el.iterator1.clone(),
+                li.el.iterator2.clone(),
+                warnings,
+            )
+        };
+        let some = |s: &str| Some(s.to_string());
+
+        assert_eq!(
+            for_parts("<ul><li v-for=\"item in items\"></li></ul>"),
+            (some("item"), some("items"), None, None, vec![])
+        );
+        assert_eq!(
+            for_parts("<ul><li v-for=\"(v, k) in obj\"></li></ul>"),
+            (some("v"), some("obj"), some("k"), None, vec![])
+        );
+        assert_eq!(
+            for_parts("<ul><li v-for=\"(v, k, i) in obj\"></li></ul>"),
+            (some("v"), some("obj"), some("k"), some("i"), vec![])
+        );
+        assert_eq!(
+            for_parts("<ul><li v-for=\"foo bar\"></li></ul>"),
+            (
+                None,
+                None,
+                None,
+                None,
+                vec!["Invalid v-for expression: foo bar".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn v_if_directive_syntax() {
+        let (ast, _warnings) = parse("<p v-if=\"show\">hello world</p>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.if_val.as_ref().unwrap(), "show");
+        assert_eq!(
+            root.el.if_conditions.as_ref().unwrap()[0]
+                .exp
+                .as_ref()
+                .unwrap(),
+            "show"
+        );
+    }
+
+    #[test]
+    fn v_else_if_directive_syntax() {
+        let (ast, _warnings) = parse("<div><p v-if=\"show\">hello</p><span v-else-if=\"2\">elseif</span><p v-else>world</p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let if_ast = root.children[0].borrow();
+        let conditions_ast = if_ast.el.if_conditions.as_ref().unwrap();
+
+        assert_eq!(conditions_ast.len(), 3);
+        assert_eq!(conditions_ast[0].block_id, 2);
+        assert_eq!(conditions_ast[0].exp.as_ref().unwrap(), "show");
+        assert_eq!(conditions_ast[1].block_id, 4);
+        assert_eq!(conditions_ast[1].exp.as_ref().unwrap(), "2");
+        assert_eq!(conditions_ast[2].block_id, 6);
+        assert_eq!(conditions_ast[2].exp, None);
+    }
+
+    #[test]
+    fn v_else_directive_syntax() {
+        let (ast, _warnings) = parse("<div><p v-if=\"show\">hello</p><p v-else>world</p></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let if_ast = root.children[0].borrow();
+        let conditions_ast = if_ast.el.if_conditions.as_ref().unwrap();
+
+        assert_eq!(conditions_ast.len(), 2);
+        assert_eq!(conditions_ast[0].block_id, 2);
+        assert_eq!(conditions_ast[0].exp.as_ref().unwrap(), "show");
+        assert_eq!(conditions_ast[1].block_id, 4);
+        assert_eq!(conditions_ast[1].exp, None);
+    }
+
+    #[test]
+    fn v_else_if_directive_invalid_syntax() {
+        let (_ast, warnings) = parse("<div><p v-else-if=\"1\">world</p></div>");
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(
+            warnings.borrow()[0],
+            "v-else-if=\"1\" used on element <p> without corresponding v-if."
+        );
+    }
+
+    #[test]
+    fn v_else_directive_invalid_syntax() {
+        let (_ast, warnings) = parse("<div><p v-else>world</p></div>");
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(
+            warnings.borrow()[0],
+            "v-else used on element <p> without corresponding v-if."
+        );
+    }
+
+    #[test]
+    fn v_once_directive_syntax() {
+        let (ast, _warnings) = parse("<p v-once>world</p>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.once, true);
+    }
+
+    #[test]
+    fn slot_tag_single_syntax() {
+        let (ast, _warnings) = parse("<div><slot></slot></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let slot = root.children[0].borrow();
+        assert_eq!(slot.el.token.data, Box::from("slot"));
+        assert_eq!(slot.el.slot_name, None);
+    }
+
+    #[test]
+    fn slot_tag_named_syntax() {
+        let (ast, _warnings) = parse("<div><slot name=\"one\">hello world</slot></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let slot = root.children[0].borrow();
+        assert_eq!(slot.el.token.data, Box::from("slot"));
+        assert_eq!(slot.el.slot_name.as_ref().unwrap(), "\"one\"");
+    }
+
+    #[test]
+    fn slot_target() {
+        let (ast, _warnings) = parse("<p slot=\"one\">hello world</p>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.slot_target.as_ref().unwrap(), "\"one\"");
+        assert_eq!(root.el.slot_target_dynamic, false);
+        // kept as an attribute for native shadow DOM
+        assert_eq!(root.el.attrs[0].name, "slot");
+        assert_eq!(root.el.attrs[0].value.as_ref().unwrap(), "\"one\"");
+
+        let (ast, _warnings) = parse("<p :slot=\"name\">hello world</p>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.slot_target.as_ref().unwrap(), "name");
+        assert_eq!(root.el.slot_target_dynamic, true);
+    }
+
+    #[test]
+    fn component_properties() {
+        let (ast, _warnings) = parse("<my-component :msg=\"hello\"></my-component>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.attrs[0].name, "msg");
+        assert_eq!(root.el.attrs[0].value, Some("hello".to_string()));
+    }
+
+    #[test]
+    fn component_is_attribute() {
+        let (ast, _warnings) = parse("<my-component is=\"component1\"></my-component>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.component.as_ref().unwrap(), "\"component1\"");
+    }
+
+    #[test]
+    fn component_inline_template_attribute() {
+        let (ast, _warnings) = parse("<my-component inline-template>hello world</my-component>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.inline_template, true);
+    }
+
+    #[test]
+    fn component_detected_before_slot_processing() {
+        let (ast, warnings) = parse("<div :is=\"comp\" v-slot=\"s\">{{ s }}</div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.component.as_ref().unwrap(), "comp");
+        assert!(root
+            .el
+            .scoped_slots
+            .as_ref()
+            .unwrap()
+            .contains_key("\"default\""));
+        assert_eq!(warnings.borrow().len(), 0);
+
+        let (_ast, warnings) = parse("<my-component v-slot=\"s\">{{ s }}</my-component>");
+        assert_eq!(warnings.borrow().len(), 0);
+
+        let (_ast, warnings) = parse("<div v-slot=\"s\">{{ s }}</div>");
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(
+            warnings.borrow()[0],
+            "v-slot can only be used on components or <template>."
+        );
+    }
+
+    #[test]
+    fn v_slot_on_component_creates_default_slot() {
+        let (ast, warnings) = parse("<MyComp v-slot=\"s\">{{s.x}}</MyComp>");
+        assert_eq!(warnings.borrow().len(), 0);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.plain, false);
+        assert_eq!(root.children.len(), 0);
+
+        let scoped_slots = root.el.scoped_slots.as_ref().unwrap();
+        assert_eq!(scoped_slots.len(), 1);
+
+        let slot_rc = scoped_slots.get("\"default\"").unwrap();
+        let slot = slot_rc.borrow();
+        assert_eq!(slot.el.token.data.as_ref(), "template");
+        assert_eq!(slot.el.slot_target.as_ref().unwrap(), "\"default\"");
+        assert_eq!(slot.el.slot_scope.as_ref().unwrap(), "s");
+        assert_eq!(slot.children.len(), 1);
+
+        let child = slot.children[0].borrow();
+        assert_eq!(child.el.expression.as_ref().unwrap(), "_s(s.x)");
+        assert_eq!(child.parent_id, slot.id);
+        assert!(Rc::ptr_eq(
+            &child.parent.as_ref().unwrap().upgrade().unwrap(),
+            slot_rc
+        ));
+    }
+
+    #[test]
+    fn warn_template_v_slot_outside_component() {
+        let (_ast, warnings) = parse("<div><template v-slot:foo>foo</template></div>");
+
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(
+            warnings.borrow()[0],
+            "<template v-slot> can only appear at the root level inside the receiving component."
+        );
+    }
+
+    #[test]
+    fn warn_invalid_dynamic_argument() {
+        const INVALID_ARGUMENT: &str = "Invalid dynamic argument expression: attribute names cannot contain spaces, quotes, <, >, / or =.";
 
-        assert_eq!(warnings.borrow().len(), 1);
-        assert_eq!(warnings.borrow()[0], "Invalid v-for expression");
+        let (_ast, warnings) = parse("<div :[foo bar]=\"x\"></div>");
+        assert!(warnings.borrow().contains(&INVALID_ARGUMENT.to_string()));
+
+        let (_ast, warnings) = parse("<div @[a b].stop=\"x\"></div>");
+        assert!(warnings.borrow().contains(&INVALID_ARGUMENT.to_string()));
+
+        let (_ast, warnings) = parse("<div :[foo]=\"x\" v-on:[event-name]=\"y\"></div>");
+        assert!(warnings.borrow().is_empty());
     }
 
     #[test]
-    fn v_if_directive_syntax() {
-        let (ast, _warnings) = parse("<p v-if=\"show\">hello world</p>");
+    fn warn_invalid_attribute_name() {
+        const INVALID_ARGUMENT: &str = "Invalid dynamic argument expression: attribute names cannot contain spaces, quotes, <, >, / or =.";
 
-        let wrapper = ast.wrapper.borrow();
-        let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.if_val.as_ref().unwrap(), "show");
-        assert_eq!(
-            root.el.if_conditions.as_ref().unwrap()[0]
-                .exp
-                .as_ref()
-                .unwrap(),
-            "show"
-        );
+        for template in [
+            "<div a\"b=\"x\"></div>",
+            "<div a'b=\"x\"></div>",
+            "<div a<b=\"x\"></div>",
+        ] {
+            let (_ast, warnings) = parse(template);
+            assert_eq!(*warnings.borrow(), vec![INVALID_ARGUMENT.to_string()]);
+        }
+
+        let (_ast, warnings) = parse("<div data-a=\"x\" aria-label=\"y\" :b.sync=\"z\"></div>");
+        assert!(warnings.borrow().is_empty());
     }
 
     #[test]
-    fn v_else_if_directive_syntax() {
-        let (ast, _warnings) = parse("<div><p v-if=\"show\">hello</p><span v-else-if=\"2\">elseif</span><p v-else>world</p></div>");
+    fn warn_directive_with_wrong_prefix() {
+        let (_ast, warnings) = parse("<div :v-if=\"x\" v-:foo=\"y\"></div>");
 
-        let wrapper = ast.wrapper.borrow();
-        let root = wrapper.children[0].borrow();
-        let if_ast = root.children[0].borrow();
-        let conditions_ast = if_ast.el.if_conditions.as_ref().unwrap();
+        assert_eq!(warnings.borrow().len(), 2);
+        assert!(warnings
+            .borrow()
+            .contains(&"Attribute \":v-if\" looks like a directive with a wrong prefix, did you mean \"v-if\"?".to_string()));
+        assert!(warnings
+            .borrow()
+            .contains(&"Attribute \"v-:foo\" looks like a directive with a wrong prefix, did you mean \":foo\"?".to_string()));
 
-        assert_eq!(conditions_ast.len(), 3);
-        assert_eq!(conditions_ast[0].block_id, 2);
-        assert_eq!(conditions_ast[0].exp.as_ref().unwrap(), "show");
-        assert_eq!(conditions_ast[1].block_id, 4);
-        assert_eq!(conditions_ast[1].exp.as_ref().unwrap(), "2");
-        assert_eq!(conditions_ast[2].block_id, 6);
-        assert_eq!(conditions_ast[2].exp, None);
+        let (_ast, warnings) =
+            parse("<div v-if=\"x\" :foo=\"y\" v-bind:bar=\"z\" @click=\"w\"></div>");
+        assert_eq!(warnings.borrow().len(), 0);
     }
 
     #[test]
-    fn v_else_directive_syntax() {
-        let (ast, _warnings) = parse("<div><p v-if=\"show\">hello</p><p v-else>world</p></div>");
+    fn component_native_event_with_modifiers() {
+        let (ast, warnings) =
+            parse("<my-comp @click.native.stop=\"fn\" @click.prevent=\"other\"></my-comp>");
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        let if_ast = root.children[0].borrow();
-        let conditions_ast = if_ast.el.if_conditions.as_ref().unwrap();
 
-        assert_eq!(conditions_ast.len(), 2);
-        assert_eq!(conditions_ast[0].block_id, 2);
-        assert_eq!(conditions_ast[0].exp.as_ref().unwrap(), "show");
-        assert_eq!(conditions_ast[1].block_id, 4);
-        assert_eq!(conditions_ast[1].exp, None);
+        let native_events = root.el.native_events.as_ref().unwrap();
+        let native_click = native_events.get("click").unwrap();
+        assert_eq!(native_click.len(), 1);
+        assert_eq!(native_click[0].value, "fn");
+        assert_eq!(native_click[0].modifiers, vec!["stop"]);
+
+        let events = root.el.events.as_ref().unwrap();
+        let click = events.get("click").unwrap();
+        assert_eq!(click.len(), 1);
+        assert_eq!(click[0].value, "other");
+        assert_eq!(click[0].modifiers, vec!["prevent"]);
+
+        assert_eq!(warnings.borrow().len(), 0);
     }
 
     #[test]
-    fn v_else_if_directive_invalid_syntax() {
-        let (_ast, warnings) = parse("<div><p v-else-if=\"1\">world</p></div>");
+    fn warnings_contain_element_tag() {
+        let (_ast, warnings) =
+            parse("<my-comp><li slot-scope=\"props\" v-for=\"item in items\"></li></my-comp>");
+        assert!(warnings.borrow().contains(&"Ambiguous combined usage of slot-scope and v-for on <li> (v-for takes higher priority). Use a wrapper <template> for the scoped slot to make it clearer.".to_string()));
+
+        let (_ast, warnings) =
+            parse("<div><p v-for=\"item in items\"><input v-model=\"item\"></p></div>");
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "<input v-model=\"item\">: You are binding v-model directly to a v-for iteration alias. This will not be able to modify the v-for source array because writing to the alias is like modifying a function local variable. Consider using an array of objects and use v-model on an object property instead.");
 
+        let (_ast, warnings) = parse("<div><style>a {}</style></div>");
         assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "Templates should only be responsible for mapping the state to the UI. Avoid placing tags with side-effects in your templates, such as <style>, as they will not be parsed.");
+    }
+
+    #[test]
+    fn v_model_on_v_for_alias_warns() {
+        let warning = |tag: &str, val: &str| {
+            format!("<{} v-model=\"{}\">: You are binding v-model directly to a v-for iteration alias. This will not be able to modify the v-for source array because writing to the alias is like modifying a function local variable. Consider using an array of objects and use v-model on an object property instead.", tag, val)
+        };
+
+        let (_ast, warnings) = parse("<div><input v-for=\"item in list\" v-model=\"item\"></div>");
+        assert_eq!(*warnings.borrow(), vec![warning("input", "item")]);
+
+        let (_ast, warnings) =
+            parse("<div><p v-for=\"(value, key) in obj\"><input v-model=\"key\"></p></div>");
+        assert_eq!(*warnings.borrow(), vec![warning("input", "key")]);
+
+        // properties of the alias and other identifiers can be written to
+        let (_ast, warnings) = parse(
+            "<div><p v-for=\"item in list\"><input v-model=\"item.name\"><input v-model=\"Item\"></p></div>",
+        );
+        assert!(warnings.borrow().is_empty());
+    }
+
+    #[test]
+    fn v_for_destructured_locals() {
+        assert_eq!(pattern_identifiers("item"), vec!["item"]);
+        assert_eq!(pattern_identifiers("{ id, name }"), vec!["id", "name"]);
         assert_eq!(
-            warnings.borrow()[0],
-            "v-else-if=\"1\" used on element <p> without corresponding v-if."
+            pattern_identifiers("[first, , ...rest]"),
+            vec!["first", "rest"]
+        );
+        assert_eq!(
+            pattern_identifiers("{ a: { b }, c: [d, e = ',' ], f = { g: 1 } }"),
+            vec!["b", "d", "e", "f"]
+        );
+
+        let warning = |val: &str| {
+            format!("<input v-model=\"{}\">: You are binding v-model directly to a v-for iteration alias. This will not be able to modify the v-for source array because writing to the alias is like modifying a function local variable. Consider using an array of objects and use v-model on an object property instead.", val)
+        };
+
+        let (_ast, warnings) =
+            parse("<div><p v-for=\"{ id, name } in items\"><input v-model=\"name\"></p></div>");
+        assert_eq!(*warnings.borrow(), vec![warning("name")]);
+
+        let (_ast, warnings) = parse(
+            "<div><p v-for=\"([first, second], i) in pairs\"><input v-model=\"second\"></p></div>",
         );
+        assert_eq!(*warnings.borrow(), vec![warning("second")]);
+
+        // object keys are not bound
+        let (_ast, warnings) =
+            parse("<div><p v-for=\"{ id: key } in items\"><input v-model=\"id\"></p></div>");
+        assert!(warnings.borrow().is_empty());
     }
 
     #[test]
-    fn v_else_directive_invalid_syntax() {
-        let (_ast, warnings) = parse("<div><p v-else>world</p></div>");
+    fn v_model_without_bindable_target_warns() {
+        let (_ast, warnings) = parse("<div v-model=\"x\"></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["<div v-model=\"x\">: v-model is not supported on this element type. If you are working with contenteditable, it's recommended to wrap a library dedicated for that purpose inside a custom component."]
+        );
 
-        assert_eq!(warnings.borrow().len(), 1);
+        let (_ast, warnings) = parse("<div><input type=\"file\" v-model=\"x\"></div>");
         assert_eq!(
-            warnings.borrow()[0],
-            "v-else used on element <p> without corresponding v-if."
+            *warnings.borrow(),
+            vec!["<input v-model=\"x\" type=\"file\">:\nFile inputs are read only. Use a v-on:change listener instead."]
+        );
+
+        // form elements and components can be bound
+        let (_ast, warnings) = parse(
+            "<div><input v-model=\"a\"><select v-model=\"b\"></select><textarea v-model=\"c\"></textarea><my-input v-model=\"d\"></my-input><div is=\"my-input\" v-model=\"e\"></div></div>",
         );
+        assert!(warnings.borrow().is_empty());
     }
 
     #[test]
-    fn v_once_directive_syntax() {
-        let (ast, _warnings) = parse("<p v-once>world</p>");
+    fn class_binding() {
+        // static
+        let (ast1, _warnings1) = parse("<p class=\"class1\">hello world</p>");
+        let wrapper1 = ast1.wrapper.borrow();
+        let root1 = wrapper1.children[0].borrow();
+        assert_eq!(root1.el.static_class.as_ref().unwrap(), "class1");
+
+        // whitespace between the class names is collapsed to a single space
+        let (ast1, _warnings1) = parse("<p class=\" class1\n   class2  \">hello world</p>");
+        let wrapper1 = ast1.wrapper.borrow();
+        let root1 = wrapper1.children[0].borrow();
+        assert_eq!(root1.el.static_class.as_ref().unwrap(), "class1 class2");
+
+        // dynamic
+        let (ast2, _warnings2) = parse("<p :class=\"class1\">hello world</p>");
+        let wrapper2 = ast2.wrapper.borrow();
+        let root2 = wrapper2.children[0].borrow();
+        assert_eq!(root2.el.class_binding.as_ref().unwrap(), "class1");
 
+        // interpolation warning
+        let (_ast3, warnings3) = parse("<p class=\"{{error}}\">hello world</p>");
+        assert_eq!(warnings3.borrow().len(), 1);
+        assert_eq!(warnings3.borrow()[0], "class=\"{{error}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div class=\"{{ val }}\">, use <div :class=\"val\">.");
+    }
+
+    #[test]
+    fn style_binding() {
+        let (ast, _warnings) = parse("<p :style=\"error\">hello world</p>");
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.once, true);
+        assert_eq!(root.el.style_binding.as_ref().unwrap(), "error");
     }
 
     #[test]
-    fn slot_tag_single_syntax() {
-        let (ast, _warnings) = parse("<div><slot></slot></div>");
+    fn style_interpolation_warning() {
+        let (_ast, warnings) = parse("<p style=\"color: {{c}}\">hello world</p>");
+        assert_eq!(warnings.borrow().len(), 1);
+        assert_eq!(warnings.borrow()[0], "style=\"color: {{c}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div style=\"{{ val }}\">, use <div :style=\"val\">.");
 
-        let wrapper = ast.wrapper.borrow();
-        let root = wrapper.children[0].borrow();
-        let slot = root.children[0].borrow();
-        assert_eq!(slot.el.token.data, Box::from("slot"));
-        assert_eq!(slot.el.slot_name, None);
+        let (_ast, warnings) = parse("<p :style=\"{ color: c }\">hello world</p>");
+        assert_eq!(warnings.borrow().len(), 0);
     }
 
     #[test]
-    fn slot_tag_named_syntax() {
-        let (ast, _warnings) = parse("<div><slot name=\"one\">hello world</slot></div>");
+    fn dynamic_event_with_modifiers() {
+        let (ast, warnings) = parse(
+            "<div @[evt].stop=\"fn\" @[obj.name].prevent.once=\"g\" @[other].right=\"h\"></div>",
+        );
+        assert_eq!(warnings.borrow().len(), 0);
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        let slot = root.children[0].borrow();
-        assert_eq!(slot.el.token.data, Box::from("slot"));
-        assert_eq!(slot.el.slot_name.as_ref().unwrap(), "one");
+        let events = root.el.events.as_ref().unwrap();
+
+        let handlers = events.get("evt").unwrap();
+        assert_eq!(handlers[0].value, "fn");
+        assert!(handlers[0].dynamic);
+        assert_eq!(handlers[0].modifiers, vec!["stop"]);
+
+        // the dots inside the brackets belong to the event name expression
+        let handlers = events.get("_p(obj.name,\"~\")").unwrap();
+        assert_eq!(handlers[0].value, "g");
+        assert!(handlers[0].dynamic);
+        assert_eq!(handlers[0].modifiers, vec!["prevent"]);
+
+        let handlers = events
+            .get("(other)==='click'?'contextmenu':(other)")
+            .unwrap();
+        assert_eq!(handlers[0].value, "h");
+        // the event may not be a click, .right stays for the arrow key filter
+        assert_eq!(handlers[0].modifiers, vec!["right"]);
     }
 
     #[test]
-    fn slot_target() {
-        let (ast, _warnings) = parse("<p slot=\"one\">hello world</p>");
+    fn v_on_object_binding() {
+        let (ast, warnings) = parse("<button v-on=\"listeners\"></button>");
+        assert!(warnings.borrow().is_empty());
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.slot_target.as_ref().unwrap(), "one");
+        assert_eq!(root.el.v_on_object.as_deref(), Some("listeners"));
+        assert!(root.el.events.is_none());
+        assert!(root.el.directives.is_none());
+        assert!(!root.el.plain);
+
+        let (_ast, warnings) = parse("<button v-on.stop=\"listeners\"></button>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec!["v-on without argument does not support modifiers."]
+        );
     }
 
     #[test]
-    fn component_properties() {
-        let (ast, _warnings) = parse("<my-component :msg=\"hello\"></my-component>");
+    fn custom_event_name_casing() {
+        let (ast, warnings) = parse(
+            "<my-comp @myCustomEvent=\"f\" v-on:mycustomevent=\"g\" :fooBar.sync=\"x\" :viewBox.camel=\"v\"></my-comp>",
+        );
+        assert_eq!(warnings.borrow().len(), 0);
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.attrs[0].name, "msg");
-        assert_eq!(root.el.attrs[0].value, Some("hello".to_string()));
+        let events = root.el.events.as_ref().unwrap();
+
+        // custom events are case sensitive, each casing has its own handlers
+        assert_eq!(events.get("myCustomEvent").unwrap()[0].value, "f");
+        assert_eq!(events.get("mycustomevent").unwrap()[0].value, "g");
+        assert_eq!(events.get("myCustomEvent").unwrap().len(), 1);
+
+        assert!(events.contains_key("update:fooBar"));
+        assert!(events.contains_key("update:foo-bar"));
+        assert!(!events.contains_key("update:foobar"));
+
+        assert_eq!(root.el.attrs[1].name, "viewBox");
     }
 
     #[test]
-    fn component_is_attribute() {
-        let (ast, _warnings) = parse("<my-component is=\"component1\"></my-component>");
+    fn dynamic_directive_arguments() {
+        let (ast, warnings) = parse(
+            "<div :[key]=\"v\" v-bind:[other.name]=\"w\" @[evt]=\"h\" v-my-dir:[arg]=\"x\"></div>",
+        );
+        assert_eq!(warnings.borrow().len(), 0);
 
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.component.as_ref().unwrap(), "component1");
+
+        assert_eq!(root.el.attrs.len(), 0);
+        assert_eq!(root.el.dynamic_attrs.len(), 2);
+        assert_eq!(root.el.dynamic_attrs[0].name, "key");
+        assert_eq!(root.el.dynamic_attrs[0].value, Some("v".to_string()));
+        assert_eq!(root.el.dynamic_attrs[0].dynamic, true);
+        assert_eq!(root.el.dynamic_attrs[1].name, "other.name");
+        assert_eq!(root.el.dynamic_attrs[1].value, Some("w".to_string()));
+
+        let events = root.el.events.as_ref().unwrap();
+        let handlers = events.get("evt").unwrap();
+        assert_eq!(handlers[0].value, "h");
+        assert_eq!(handlers[0].dynamic, true);
+
+        let directives = root.el.directives.as_ref().unwrap();
+        assert_eq!(directives.len(), 1);
+        assert_eq!(directives[0].name, "my-dir");
+        assert_eq!(directives[0].arg, Some("arg".to_string()));
+        assert_eq!(directives[0].is_dynamic_arg, true);
+        assert_eq!(directives[0].value, Some("x".to_string()));
     }
 
     #[test]
-    fn component_inline_template_attribute() {
-        let (ast, _warnings) = parse("<my-component inline-template>hello world</my-component>");
+    fn compat_number_modifier_hint() {
+        let template = "<comp :count.number=\"x\" :label=\"y\"></comp>";
+
+        let options = CompilerOptions {
+            compat_number_modifier: true,
+            ..Default::default()
+        };
+        let ast = parse_with_options(template, &options);
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.number_bindings, vec!["count"]);
+        assert_eq!(root.el.attrs[0].name, "count");
+        assert_eq!(root.el.attrs[0].value, Some("x".to_string()));
 
+        let (ast, _warnings) = parse(template);
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.inline_template, true);
+        assert!(root.el.number_bindings.is_empty());
     }
 
     #[test]
-    fn class_binding() {
-        // static
-        let (ast1, _warnings1) = parse("<p class=\"class1\">hello world</p>");
-        let wrapper1 = ast1.wrapper.borrow();
-        let root1 = wrapper1.children[0].borrow();
-        assert_eq!(root1.el.static_class.as_ref().unwrap(), "class1");
+    fn v_bind_object_syntax() {
+        let (ast, warnings) =
+            parse("<div v-bind=\"{ id: x }\"><comp v-bind.prop.sync=\"obj\"></comp></div>");
+        assert_eq!(warnings.borrow().len(), 0);
 
-        // dynamic
-        let (ast2, _warnings2) = parse("<p :class=\"class1\">hello world</p>");
-        let wrapper2 = ast2.wrapper.borrow();
-        let root2 = wrapper2.children[0].borrow();
-        assert_eq!(root2.el.class_binding.as_ref().unwrap(), "class1");
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let v_bind_object = root.el.v_bind_object.as_ref().unwrap();
+        assert_eq!(v_bind_object.value, "{ id: x }");
+        assert_eq!(v_bind_object.prop, false);
+        assert_eq!(v_bind_object.sync, false);
+        assert_eq!(root.el.plain, false);
+        assert_eq!(root.el.attrs.len(), 0);
+        assert!(root.el.directives.is_none());
 
-        // interpolation warning
-        let (_ast3, warnings3) = parse("<p class=\"{{error}}\">hello world</p>");
-        assert_eq!(warnings3.borrow().len(), 2);
-        assert_eq!(warnings3.borrow()[0], "class=\"{{error}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div class=\"{ val }\">, use <div :class=\"val\">.");
+        let comp = root.children[0].borrow();
+        let v_bind_object = comp.el.v_bind_object.as_ref().unwrap();
+        assert_eq!(v_bind_object.value, "obj");
+        assert_eq!(v_bind_object.prop, true);
+        assert_eq!(v_bind_object.sync, true);
     }
 
     #[test]
-    fn style_binding() {
-        let (ast, _warnings) = parse("<p :style=\"error\">hello world</p>");
+    fn consumed_attributes_are_not_processed_again() {
+        let (ast, warnings) = parse(
+            "<div v-if=\"ok\"><p v-for=\"item in items\" :key=\"item.id\" v-once ref=\"p\" class=\"a\"></p></div>",
+        );
+        assert_eq!(warnings.borrow().len(), 0);
+
         let wrapper = ast.wrapper.borrow();
         let root = wrapper.children[0].borrow();
-        assert_eq!(root.el.style_binding.as_ref().unwrap(), "error");
+        assert!(root.el.directives.is_none());
+        assert_eq!(root.el.has_bindings, false);
+
+        let p = root.children[0].borrow();
+        assert!(p.el.directives.is_none());
+        assert_eq!(p.el.attrs.len(), 0);
+        assert_eq!(p.el.has_bindings, false);
     }
 
     #[test]
@@ -1008,6 +3177,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn empty_directive_values() {
+        let (ast, warnings) = parse("<div><p v-if=\"\"></p><p :foo></p><p @click></p></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec![
+                "Missing v-if expression.",
+                "The value for a v-bind expression cannot be empty. Found in \"v-bind:foo\"",
+                "The value for a v-on expression cannot be empty. Found in \"v-on:click\"",
+            ]
+        );
+        let binding = ast.wrapper.borrow();
+        let div = binding.children[0].borrow();
+        assert!(div.children[0].borrow().el.if_val.is_none());
+        assert!(div.children[2].borrow().el.events.is_none());
+
+        let (_ast, warnings) =
+            parse("<div><p v-show=\"\"></p><input v-model=\" \"><p v-show></p></div>");
+        assert_eq!(
+            *warnings.borrow(),
+            vec![
+                "The value for a v-show expression cannot be empty. Found in \"v-show\"",
+                "The value for a v-model expression cannot be empty. Found in \"v-model\"",
+                "The value for a v-show expression cannot be empty. Found in \"v-show\"",
+            ]
+        );
+
+        // modifiers alone are a valid handler
+        let (ast, warnings) = parse("<form @submit.prevent></form>");
+        assert!(warnings.borrow().is_empty());
+        let binding = ast.wrapper.borrow();
+        assert!(binding.children[0].borrow().el.events.is_some());
+    }
+
     fn parse_v_bind_on(template: &str) -> (ASTTree, Rc<RefCell<Vec<String>>>) {
         let warnings = Rc::new(RefCell::new(Vec::new()));
         let warnings_clone = Rc::clone(&warnings);
@@ -1017,18 +3220,31 @@ mod tests {
             v_bind_prop_short_hand: true,
             preserve_comments: false,
             whitespace_handling: WhitespaceHandling::Condense,
+            trim_block_whitespace: false,
             new_slot_syntax: true,
+            auto_key_v_for: false,
+            compat_number_modifier: false,
+            output_source_range: false,
+            allow_multiple_roots: false,
+            component_name: None,
             is_pre_tag: None,
-            get_namespace: None,
+            is_unary_tag: None,
+            get_tag_namespace: None,
+            resolve_component: None,
             warn: Some(Box::new(move |msg: &str| {
                 warnings_clone.borrow_mut().push(msg.to_string());
             })),
             delimiters: None,
+            entities: None,
+            helpers: RenderHelpers::default(),
             modules: Some(vec![
                 Box::new(ClassModule {}),
                 Box::new(ModelModule {}),
                 Box::new(StyleModule {}),
             ]),
+            pre_transforms: None,
+            transforms: None,
+            post_transforms: None,
         };
         let mut parser = VueParser::new(&options);
 
@@ -1163,6 +3379,27 @@ mod tests {
         assert_eq!(root.el.props[0].value, Some("true".to_string()));
     }
 
+    #[test]
+    fn value_binding_uses_dom_prop() {
+        let (ast, _warnings) = parse(
+            "<div><input :value=\"a\"><input type=\"button\" :value=\"b\"><textarea :value=\"c\"></textarea></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        // an input without a type is a text input
+        let input = root.children[0].borrow();
+        assert_eq!(input.el.props[0].name, "value");
+        assert!(input.el.attrs.is_empty());
+
+        let button = root.children[1].borrow();
+        assert!(button.el.props.is_empty());
+        assert!(button.el.attrs.iter().any(|attr| attr.name == "value"));
+
+        let textarea = root.children[2].borrow();
+        assert_eq!(textarea.el.props[0].name, "value");
+    }
+
     #[test]
     fn attribute_with_v_on() {
         let (ast, _warnings) =
@@ -1188,6 +3425,36 @@ mod tests {
         assert_eq!(&directive.arg, &Some("field1".to_string()));
     }
 
+    #[test]
+    fn static_attributes_in_source_order() {
+        let (ast, _warnings) = parse("<div id=\"a\" title=\"b\"></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.attrs.len(), 2);
+        assert_eq!(root.el.attrs[0].name, "id");
+        assert_eq!(root.el.attrs[0].value, Some("a".to_string()));
+        assert_eq!(root.el.attrs[1].name, "title");
+        assert_eq!(root.el.attrs[1].value, Some("b".to_string()));
+
+        let (ast, _warnings) = parse(
+            "<div title=\"a &amp; b\" :foo=\"bar\" data-x=\"1\" aria-label=\"&lt;x&gt;\"></div>",
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        let names: Vec<&str> = root
+            .el
+            .attrs
+            .iter()
+            .map(|attr| attr.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["title", "foo", "data-x", "aria-label"]);
+        assert_eq!(root.el.attrs[0].value, Some("a & b".to_string()));
+        assert_eq!(root.el.attrs[1].value, Some("bar".to_string()));
+        assert_eq!(root.el.attrs[3].value, Some("<x>".to_string()));
+    }
+
     #[test]
     fn attribute_with_modified_directive() {
         let (ast, _warnings) =
@@ -1208,17 +3475,31 @@ mod tests {
 
         let wrapper1 = ast1.wrapper.borrow();
         let root1 = wrapper1.children[0].borrow();
-        assert_eq!(root1.el.attrs[2].name, "name");
-        assert_eq!(root1.el.attrs[2].value, Some("field1".to_string()));
-        assert_eq!(root1.el.attrs[1].name, "type");
-        assert_eq!(root1.el.attrs[1].value, Some("text".to_string()));
-        assert_eq!(root1.el.attrs[0].name, "value");
-        assert_eq!(root1.el.attrs[0].value, Some("hello world".to_string()));
+        assert_eq!(root1.el.attrs[0].name, "type");
+        assert_eq!(root1.el.attrs[0].value, Some("text".to_string()));
+        assert_eq!(root1.el.attrs[1].name, "name");
+        assert_eq!(root1.el.attrs[1].value, Some("field1".to_string()));
+        assert_eq!(root1.el.attrs[2].name, "value");
+        assert_eq!(root1.el.attrs[2].value, Some("hello world".to_string()));
 
         let (_ast2, warnings2) = parse("<input type=\"text\" name=\"field1\" value=\"{{msg}}\">");
 
         assert_eq!(warnings2.borrow().len(), 1);
-        assert_eq!(warnings2.borrow()[0], "value=\"{{msg}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{ val }\">, use <div :id=\"val\">.");
+        assert_eq!(warnings2.borrow()[0], "value=\"{{msg}}\": Interpolation inside attributes has been removed. Use v-bind or the colon shorthand instead. For example, instead of <div id=\"{{ val }}\">, use <div :id=\"val\">.");
+    }
+
+    #[test]
+    fn decode_entities_in_attribute_values() {
+        let (ast, _warnings) =
+            parse("<div title=\"a &amp; b\" data-quote=\"it&#39;s\" data-lt=\"&lt;p&gt;\" data-raw=\"&amp;lt;\"></div>");
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.el.attrs[0].value, Some("a & b".to_string()));
+        assert_eq!(root.el.attrs[1].value, Some("it's".to_string()));
+        assert_eq!(root.el.attrs[2].value, Some("<p>".to_string()));
+        // decoded exactly once
+        assert_eq!(root.el.attrs[3].value, Some("&lt;".to_string()));
     }
 
     #[test]
@@ -1240,7 +3521,7 @@ mod tests {
     // #[test]
     // fn not_specified_get_tag_namespace_option() {
     //     let options = CompilerOptions {
-    //         get_namespace: None,
+    //         get_tag_namespace: None,
     //         ..Default::default()
     //     };
     //     let (ast, _warnings) = parse_with_options("<svg><text>hello world</text></svg>", options);
@@ -1309,6 +3590,37 @@ mod tests {
         assert_eq!(span.children[0].borrow().el.token.data, Box::from(" "));
     }
 
+    #[test]
+    fn trim_boundary_whitespace_on_close() {
+        let options = CompilerOptions {
+            whitespace_handling: WhitespaceHandling::Preserve,
+            ..Default::default()
+        };
+        let ast = parse_with_options("<div> <span/> </div>", &options);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children.len(), 1);
+        let span = root.children[0].borrow();
+        assert_eq!(span.el.token.data, Box::from("span"));
+        assert_eq!(span.children.len(), 0);
+
+        // interior whitespace is still subject to the whitespace option
+        let ast = parse_with_options("<div> <a></a> <b></b> </div>", &options);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children.len(), 3);
+        assert_eq!(root.children[1].borrow().el.token.data, Box::from(" "));
+
+        // kept inside <pre>
+        let ast = parse_with_options("<pre> <a></a> </pre>", &options);
+
+        let wrapper = ast.wrapper.borrow();
+        let root = wrapper.children[0].borrow();
+        assert_eq!(root.children.len(), 3);
+    }
+
     // This is weird requirement, it does not handle \r\n but only handles \n
     // So as its weird and buggy it is not implemented for now
     // #[test]
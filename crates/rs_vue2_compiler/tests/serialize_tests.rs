@@ -0,0 +1,116 @@
+#![cfg(feature = "serde")]
+
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::{CompilerOptions, VueParser};
+
+    #[test]
+    fn serialize_source_ranges() {
+        let options = CompilerOptions {
+            output_source_range: true,
+            ..Default::default()
+        };
+        let ast = VueParser::new(&options).parse("<div id=\"a\">\n  <br/><p>{{ x }}</p></div>");
+
+        assert_eq!(
+            serde_json::to_string(&ast).unwrap(),
+            concat!(
+                r#"{"id":0,"data":"","kind":"Root","children":[{"id":1,"parent":0,"component":false,"attr_names":["id"],"#,
+                r#""attr_ranges":{"id":{"column":10,"end":10,"line":1,"start":9}},"attrs":[{"literal":true,"name":"id","value":"a"}],"#,
+                r#""data":"div","kind":"Element","range":{"column":1,"end":40,"line":1,"start":0},"children":["#,
+                r#"{"id":2,"parent":1,"component":false,"data":"br","kind":"Element","plain":true,"range":{"column":3,"end":20,"line":2,"start":15},"children":[]},"#,
+                r#"{"id":3,"parent":1,"component":false,"data":"p","kind":"Element","plain":true,"range":{"column":8,"end":34,"line":2,"start":20},"children":["#,
+                r#"{"id":4,"parent":3,"data":"{{ x }}","expression":"_s(x)","kind":"Expression","range":{"column":11,"end":30,"line":2,"start":23},"tokens":["@binding: x"],"children":[]}]}]}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn to_json_snapshot() {
+        let options = CompilerOptions::default();
+        let ast =
+            VueParser::new(&options).parse("<ul><li v-for=\"item in items\">{{ item }}</li></ul>");
+
+        assert_eq!(
+            ast.to_json(),
+            concat!(
+                r#"{"id":0,"data":"","kind":"Root","children":[{"id":1,"parent":0,"component":false,"data":"ul","kind":"Element","normalization_type":"Full","plain":true,"children":["#,
+                r#"{"id":2,"parent":1,"component":false,"alias":"item","attr_names":["v-for"],"data":"li","for_value":"items","kind":"Element","children":["#,
+                r#"{"id":3,"parent":2,"data":"{{ item }}","expression":"_s(item)","kind":"Expression","tokens":["@binding: item"],"children":[]}]}]}]}"#
+            )
+        );
+
+        let wrapper = ast.wrapper.borrow();
+        let ul = wrapper.children[0].borrow();
+        let li = ul.children[0].borrow();
+        assert_eq!(
+            li.to_json(),
+            concat!(
+                r#"{"id":2,"parent":1,"component":false,"alias":"item","attr_names":["v-for"],"data":"li","for_value":"items","kind":"Element","children":["#,
+                r#"{"id":3,"parent":2,"data":"{{ item }}","expression":"_s(item)","kind":"Expression","tokens":["@binding: item"],"children":[]}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn source_ranges_are_opt_in() {
+        let options = CompilerOptions::default();
+        let ast = VueParser::new(&options).parse("<div></div>");
+
+        assert_eq!(
+            serde_json::to_string(&ast).unwrap(),
+            r#"{"id":0,"data":"","kind":"Root","children":[{"id":1,"parent":0,"component":false,"data":"div","kind":"Element","plain":true,"children":[]}]}"#
+        );
+    }
+
+    #[test]
+    fn serialize_component_flags() {
+        let options = CompilerOptions::default();
+        let ast = VueParser::new(&options)
+            .parse("<div><my-comp></my-comp><div is=\"other-comp\"></div><component :is=\"view\"></component><span></span></div>");
+
+        assert_eq!(
+            serde_json::to_string(&ast).unwrap(),
+            concat!(
+                r#"{"id":0,"data":"","kind":"Root","children":[{"id":1,"parent":0,"component":false,"data":"div","kind":"Element","normalization_type":"Simple","plain":true,"children":["#,
+                r#"{"id":2,"parent":1,"component":true,"data":"my-comp","kind":"Element","plain":true,"children":[]},"#,
+                r#"{"id":3,"parent":1,"component":true,"attr_names":["is"],"data":"div","is":"\"other-comp\"","kind":"Element","children":[]},"#,
+                r#"{"id":4,"parent":1,"component":true,"attr_names":[":is"],"data":"component","is":"view","kind":"Element","children":[]},"#,
+                r#"{"id":5,"parent":1,"component":false,"data":"span","kind":"Element","plain":true,"children":[]}]}]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn serialize_if_conditions_and_scoped_slots() {
+        let options = CompilerOptions::default();
+        let ast = VueParser::new(&options).parse(
+            "<div><p v-if=\"a\">a</p><p v-else>b</p><comp><template #item=\"{ x }\">{{ x }}</template></comp></div>",
+        );
+
+        assert_eq!(
+            ast.to_json(),
+            concat!(
+                r#"{"id":0,"data":"","kind":"Root","children":[{"id":1,"parent":0,"component":false,"data":"div","kind":"Element","normalization_type":"Simple","plain":true,"children":["#,
+                r#"{"id":2,"parent":1,"component":false,"attr_names":["v-if"],"data":"p","if_val":"a","kind":"Element","if_conditions":[{"exp":"a","block_id":2},"#,
+                r#"{"exp":null,"block_id":4,"block":{"id":4,"parent":1,"component":false,"attr_names":["v-else"],"data":"p","is_else":true,"kind":"Element","children":["#,
+                r#"{"id":5,"parent":4,"data":"b","kind":"Text","children":[]}]}}],"children":[{"id":3,"parent":2,"data":"a","kind":"Text","children":[]}]},"#,
+                r#"{"id":6,"parent":1,"component":true,"data":"comp","kind":"Element","scoped_slots":{"\"item\"":{"id":7,"parent":6,"component":false,"#,
+                r##""attr_names":["#item"],"data":"template","kind":"Element","slot_scope":"{ x }","slot_target":"\"item\"","v_slot":true,"children":["##,
+                r#"{"id":8,"parent":7,"data":"{{ x }}","expression":"_s(x)","kind":"Expression","tokens":["@binding: x"],"children":[]}]}},"children":[]}]}]}"#
+            )
+        );
+
+        // without the tree the other branches are only referenced by id
+        let wrapper = ast.wrapper.borrow();
+        let div = wrapper.children[0].borrow();
+        let p = div.children[0].borrow();
+        assert_eq!(
+            p.to_json(),
+            concat!(
+                r#"{"id":2,"parent":1,"component":false,"attr_names":["v-if"],"data":"p","if_val":"a","kind":"Element","#,
+                r#""if_conditions":[{"exp":"a","block_id":2},{"exp":null,"block_id":4}],"children":[{"id":3,"parent":2,"data":"a","kind":"Text","children":[]}]}"#
+            )
+        );
+    }
+}
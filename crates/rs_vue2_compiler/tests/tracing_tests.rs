@@ -0,0 +1,78 @@
+#![cfg(feature = "tracing")]
+
+#[cfg(test)]
+mod tests {
+    use rs_vue2_compiler::codegen::generate;
+    use rs_vue2_compiler::optimizer::optimize;
+    use rs_vue2_compiler::trace::TRACE_TARGET;
+    use rs_vue2_compiler::{CompilerOptions, VueParser};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    // records "enter <span>" and "exit <span>" for the spans of the compiler
+    #[derive(Default)]
+    struct CaptureSubscriber {
+        next_id: AtomicU64,
+        names: Mutex<Vec<&'static str>>,
+        records: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl CaptureSubscriber {
+        fn name(&self, span: &Id) -> &'static str {
+            self.names.lock().unwrap()[span.into_u64() as usize - 1]
+        }
+    }
+
+    impl Subscriber for CaptureSubscriber {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == TRACE_TARGET
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.names.lock().unwrap().push(span.metadata().name());
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            let record = format!("enter {}", self.name(span));
+            self.records.lock().unwrap().push(record);
+        }
+
+        fn exit(&self, span: &Id) {
+            let record = format!("exit {}", self.name(span));
+            self.records.lock().unwrap().push(record);
+        }
+    }
+
+    #[test]
+    fn emits_phase_spans() {
+        let subscriber = CaptureSubscriber::default();
+        let records = Arc::clone(&subscriber.records);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let options = CompilerOptions::default();
+            let mut ast = VueParser::new(&options).parse("<div><p v-if=\"ok\">{{ msg }}</p></div>");
+            optimize(&mut ast);
+            generate(&ast, &options);
+        });
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.first().unwrap(), "enter parse");
+        for phase in ["parse", "process", "optimize", "codegen"] {
+            assert!(records.contains(&format!("enter {}", phase)));
+            assert!(records.contains(&format!("exit {}", phase)));
+        }
+        // every process span is nested in the parse span
+        let parse_exit = records.iter().position(|r| r == "exit parse").unwrap();
+        let last_process_exit = records.iter().rposition(|r| r == "exit process").unwrap();
+        assert!(last_process_exit < parse_exit);
+    }
+}